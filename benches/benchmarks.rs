@@ -5,7 +5,12 @@ use rand::rngs::StdRng;
 use rand::Rng;
 use rand::SeedableRng;
 use test::Bencher;
+use tree_list::arena_tree_list::ArenaTreeList;
+use tree_list::balance::WeightBalanced;
+use tree_list::chunked_tree_list::ChunkedTreeList;
 use tree_list::recursive_tree_list::RecursiveTreeList;
+use tree_list::splay_tree_list::SplayTreeList;
+use tree_list::treap_list::TreapList;
 use tree_list::tree_list::TreeList;
 
 #[bench]
@@ -17,7 +22,7 @@ fn tree_insert_delete_random_1000(bencher: &mut Bencher) {
 
         for i in 0..1000 {
             let ind = rng.gen_range(0, i + 1);
-            let c = rng.gen_range('a' as u8, 'z' as u8 + 1) as char;
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
             tree.insert(ind, c);
         }
 
@@ -39,7 +44,7 @@ fn rec_tree_insert_delete_random_1000(bencher: &mut Bencher) {
 
         for i in 0..1000 {
             let ind = rng.gen_range(0, i + 1);
-            let c = rng.gen_range('a' as u8, 'z' as u8 + 1) as char;
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
             tree.insert(ind, c);
         }
 
@@ -61,7 +66,7 @@ fn vec_insert_delete_random_1000(bencher: &mut Bencher) {
 
         for i in 0..1000 {
             let ind = rng.gen_range(0, i + 1);
-            let c = rng.gen_range('a' as u8, 'z' as u8 + 1) as char;
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
             vec.insert(ind, c);
         }
 
@@ -83,7 +88,7 @@ fn tree_insert_delete_random_10000(bencher: &mut Bencher) {
 
         for i in 0..10000 {
             let ind = rng.gen_range(0, i + 1);
-            let c = rng.gen_range('a' as u8, 'z' as u8 + 1) as char;
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
             tree.insert(ind, c);
         }
 
@@ -105,7 +110,7 @@ fn rec_tree_insert_delete_random_10000(bencher: &mut Bencher) {
 
         for i in 0..10000 {
             let ind = rng.gen_range(0, i + 1);
-            let c = rng.gen_range('a' as u8, 'z' as u8 + 1) as char;
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
             tree.insert(ind, c);
         }
 
@@ -127,7 +132,7 @@ fn vec_insert_delete_random_10000(bencher: &mut Bencher) {
 
         for i in 0..10000 {
             let ind = rng.gen_range(0, i + 1);
-            let c = rng.gen_range('a' as u8, 'z' as u8 + 1) as char;
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
             vec.insert(ind, c);
         }
 
@@ -149,7 +154,7 @@ fn tree_insert_delete_random_20000(bencher: &mut Bencher) {
 
         for i in 0..20000 {
             let ind = rng.gen_range(0, i + 1);
-            let c = rng.gen_range('a' as u8, 'z' as u8 + 1) as char;
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
             tree.insert(ind, c);
         }
 
@@ -171,7 +176,7 @@ fn rec_tree_insert_delete_random_20000(bencher: &mut Bencher) {
 
         for i in 0..20000 {
             let ind = rng.gen_range(0, i + 1);
-            let c = rng.gen_range('a' as u8, 'z' as u8 + 1) as char;
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
             tree.insert(ind, c);
         }
 
@@ -193,7 +198,7 @@ fn vec_insert_delete_random_20000(bencher: &mut Bencher) {
 
         for i in 0..20000 {
             let ind = rng.gen_range(0, i + 1);
-            let c = rng.gen_range('a' as u8, 'z' as u8 + 1) as char;
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
             vec.insert(ind, c);
         }
 
@@ -215,7 +220,7 @@ fn tree_insert_delete_random_30000(bencher: &mut Bencher) {
 
         for i in 0..30000 {
             let ind = rng.gen_range(0, i + 1);
-            let c = rng.gen_range('a' as u8, 'z' as u8 + 1) as char;
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
             tree.insert(ind, c);
         }
 
@@ -237,7 +242,7 @@ fn rec_tree_insert_delete_random_30000(bencher: &mut Bencher) {
 
         for i in 0..30000 {
             let ind = rng.gen_range(0, i + 1);
-            let c = rng.gen_range('a' as u8, 'z' as u8 + 1) as char;
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
             tree.insert(ind, c);
         }
 
@@ -259,7 +264,7 @@ fn vec_insert_delete_random_30000(bencher: &mut Bencher) {
 
         for i in 0..30000 {
             let ind = rng.gen_range(0, i + 1);
-            let c = rng.gen_range('a' as u8, 'z' as u8 + 1) as char;
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
             vec.insert(ind, c);
         }
 
@@ -281,7 +286,7 @@ fn tree_insert_delete_random_40000(bencher: &mut Bencher) {
 
         for i in 0..40000 {
             let ind = rng.gen_range(0, i + 1);
-            let c = rng.gen_range('a' as u8, 'z' as u8 + 1) as char;
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
             tree.insert(ind, c);
         }
 
@@ -303,7 +308,7 @@ fn rec_tree_insert_delete_random_40000(bencher: &mut Bencher) {
 
         for i in 0..40000 {
             let ind = rng.gen_range(0, i + 1);
-            let c = rng.gen_range('a' as u8, 'z' as u8 + 1) as char;
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
             tree.insert(ind, c);
         }
 
@@ -325,7 +330,7 @@ fn vec_insert_delete_random_40000(bencher: &mut Bencher) {
 
         for i in 0..40000 {
             let ind = rng.gen_range(0, i + 1);
-            let c = rng.gen_range('a' as u8, 'z' as u8 + 1) as char;
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
             vec.insert(ind, c);
         }
 
@@ -337,3 +342,207 @@ fn vec_insert_delete_random_40000(bencher: &mut Bencher) {
         vec.clear();
     });
 }
+
+#[bench]
+fn tree_push_back_sequential_40000(bencher: &mut Bencher) {
+    bencher.iter(|| {
+        let mut tree: TreeList<usize> = TreeList::new();
+
+        for i in 0..40000 {
+            tree.push_back(i);
+        }
+
+        for i in 0..40000 {
+            test::black_box(tree.get(i));
+        }
+
+        tree.clear();
+    });
+}
+
+#[bench]
+fn tree_from_vec_40000(bencher: &mut Bencher) {
+    bencher.iter(|| {
+        let tree: TreeList<usize> = TreeList::from_vec((0..40000).collect());
+
+        for i in 0..40000 {
+            test::black_box(tree.get(i));
+        }
+    });
+}
+
+#[bench]
+fn rec_tree_push_back_sequential_40000(bencher: &mut Bencher) {
+    bencher.iter(|| {
+        let mut tree: RecursiveTreeList<usize> = RecursiveTreeList::new();
+
+        for i in 0..40000 {
+            tree.push_back(i);
+        }
+
+        for i in 0..40000 {
+            test::black_box(tree.get(i));
+        }
+
+        tree.clear();
+    });
+}
+
+#[bench]
+fn vec_push_back_sequential_40000(bencher: &mut Bencher) {
+    bencher.iter(|| {
+        let mut vec: Vec<usize> = Vec::new();
+
+        for i in 0..40000 {
+            vec.push(i);
+        }
+
+        for i in 0..40000 {
+            test::black_box(vec.get(i));
+        }
+
+        vec.clear();
+    });
+}
+
+#[bench]
+fn tree_weight_balanced_insert_delete_random_40000(bencher: &mut Bencher) {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    bencher.iter(|| {
+        let mut tree: TreeList<char, WeightBalanced> = TreeList::with_policy();
+
+        for i in 0..40000 {
+            let ind = rng.gen_range(0, i + 1);
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
+            tree.insert(ind, c);
+        }
+
+        for i in (0..20000).rev() {
+            let ind = rng.gen_range(0, i + 1);
+            tree.remove(ind);
+        }
+
+        tree.clear();
+    });
+}
+
+#[bench]
+fn treap_insert_delete_random_40000(bencher: &mut Bencher) {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    bencher.iter(|| {
+        let mut tree: TreapList<char> = TreapList::with_seed(0);
+
+        for i in 0..40000 {
+            let ind = rng.gen_range(0, i + 1);
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
+            tree.insert(ind, c);
+        }
+
+        for i in (0..20000).rev() {
+            let ind = rng.gen_range(0, i + 1);
+            tree.remove(ind);
+        }
+
+        tree.clear();
+    });
+}
+
+#[bench]
+fn tree_local_access_40000(bencher: &mut Bencher) {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut tree: TreeList<usize> = TreeList::new();
+    for i in 0..40000 {
+        tree.push_back(i);
+    }
+
+    bencher.iter(|| {
+        let mut cursor = 20000;
+        for _ in 0..40000 {
+            cursor = cursor + rng.gen_range(0, 5) - 2;
+            test::black_box(tree.get(cursor));
+        }
+    });
+}
+
+#[bench]
+fn splay_local_access_40000(bencher: &mut Bencher) {
+    let mut rng = StdRng::seed_from_u64(0);
+    let mut tree: SplayTreeList<usize> = SplayTreeList::new();
+    for i in 0..40000 {
+        tree.push_back(i);
+    }
+
+    bencher.iter(|| {
+        let mut cursor = 20000;
+        for _ in 0..40000 {
+            cursor = cursor + rng.gen_range(0, 5) - 2;
+            test::black_box(tree.get(cursor));
+        }
+    });
+}
+
+#[bench]
+fn chunked_insert_delete_random_40000(bencher: &mut Bencher) {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    bencher.iter(|| {
+        let mut tree: ChunkedTreeList<char> = ChunkedTreeList::new();
+
+        for i in 0..40000 {
+            let ind = rng.gen_range(0, i + 1);
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
+            tree.insert(ind, c);
+        }
+
+        for i in (0..20000).rev() {
+            let ind = rng.gen_range(0, i + 1);
+            tree.remove(ind);
+        }
+
+        tree.clear();
+    });
+}
+
+#[bench]
+fn tree_iter_40000(bencher: &mut Bencher) {
+    let mut tree: TreeList<usize> = TreeList::new();
+    for i in 0..40000 {
+        tree.push_back(i);
+    }
+
+    bencher.iter(|| tree.iter().sum::<usize>());
+}
+
+#[bench]
+fn chunked_iter_40000(bencher: &mut Bencher) {
+    let mut tree: ChunkedTreeList<usize> = ChunkedTreeList::new();
+    for i in 0..40000 {
+        tree.push_back(i);
+    }
+
+    bencher.iter(|| tree.iter().sum::<usize>());
+}
+
+#[bench]
+fn arena_insert_delete_random_40000(bencher: &mut Bencher) {
+    let mut rng = StdRng::seed_from_u64(0);
+
+    bencher.iter(|| {
+        let mut tree: ArenaTreeList<char> = ArenaTreeList::new();
+
+        for i in 0..40000 {
+            let ind = rng.gen_range(0, i + 1);
+            let c = rng.gen_range(b'a', b'z' + 1) as char;
+            tree.insert(ind, c);
+        }
+
+        for i in (0..20000).rev() {
+            let ind = rng.gen_range(0, i + 1);
+            tree.remove(ind);
+        }
+
+        tree.clear();
+    });
+}
@@ -0,0 +1,434 @@
+use crate::balance::{Avl, Rotation};
+use std::cmp::Ordering;
+
+// Links are indices into the arena, with `NIL` standing in for `None`.
+const NIL: u32 = u32::MAX;
+
+#[derive(Debug)]
+struct TreeNode<T> {
+    val: T,
+    num_to_left: u32,
+    height: u8,
+    left: u32,
+    right: u32,
+}
+
+impl<T> TreeNode<T> {
+    fn new(val: T) -> Self {
+        TreeNode {
+            val,
+            num_to_left: 0,
+            height: 1,
+            left: NIL,
+            right: NIL,
+        }
+    }
+}
+
+// A vacant slot links to the next vacant slot, forming a free list.
+#[derive(Debug)]
+enum Slot<T> {
+    Occupied(TreeNode<T>),
+    Vacant(u32),
+}
+
+#[derive(Debug)]
+pub struct ArenaTreeList<T> {
+    nodes: Vec<Slot<T>>,
+    free: u32,
+    root: u32,
+    size: usize,
+}
+
+impl<T> Default for ArenaTreeList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> ArenaTreeList<T> {
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        ArenaTreeList {
+            nodes: Vec::with_capacity(capacity),
+            free: NIL,
+            root: NIL,
+            size: 0,
+        }
+    }
+
+    fn node(&self, index: u32) -> &TreeNode<T> {
+        match &self.nodes[index as usize] {
+            Slot::Occupied(node) => node,
+            Slot::Vacant(_) => unreachable!(),
+        }
+    }
+
+    fn node_mut(&mut self, index: u32) -> &mut TreeNode<T> {
+        match &mut self.nodes[index as usize] {
+            Slot::Occupied(node) => node,
+            Slot::Vacant(_) => unreachable!(),
+        }
+    }
+
+    fn alloc(&mut self, val: T) -> u32 {
+        let node = Slot::Occupied(TreeNode::new(val));
+        if self.free == NIL {
+            assert!(self.nodes.len() < NIL as usize, "Arena is full!");
+            self.nodes.push(node);
+            (self.nodes.len() - 1) as u32
+        } else {
+            let index = self.free;
+            match std::mem::replace(&mut self.nodes[index as usize], node) {
+                Slot::Vacant(next) => self.free = next,
+                Slot::Occupied(_) => unreachable!(),
+            }
+            index
+        }
+    }
+
+    fn dealloc(&mut self, index: u32) -> T {
+        let slot = std::mem::replace(&mut self.nodes[index as usize], Slot::Vacant(self.free));
+        self.free = index;
+        match slot {
+            Slot::Occupied(node) => node.val,
+            Slot::Vacant(_) => unreachable!(),
+        }
+    }
+
+    fn height(&self, index: u32) -> usize {
+        if index == NIL {
+            0
+        } else {
+            self.node(index).height as usize
+        }
+    }
+
+    fn children(&self, index: u32) -> (u32, u32) {
+        let node = self.node(index);
+        (node.left, node.right)
+    }
+
+    fn update_height(&mut self, index: u32) {
+        let node = self.node(index);
+        let height = Avl::parent_height(self.height(node.left), self.height(node.right));
+        self.node_mut(index).height = height as u8;
+    }
+
+    fn rotate_left(&mut self, index: u32) -> u32 {
+        let right = self.node(index).right;
+        let right_left = self.node(right).left;
+        let num_to_left = self.node(index).num_to_left;
+
+        self.node_mut(index).right = right_left;
+        self.update_height(index);
+        let node = self.node_mut(right);
+        node.num_to_left += num_to_left + 1;
+        node.left = index;
+        self.update_height(right);
+        right
+    }
+
+    fn rotate_right(&mut self, index: u32) -> u32 {
+        let left = self.node(index).left;
+        let left_right = self.node(left).right;
+        let left_num_to_left = self.node(left).num_to_left;
+
+        let node = self.node_mut(index);
+        node.left = left_right;
+        node.num_to_left -= left_num_to_left + 1;
+        self.update_height(index);
+        self.node_mut(left).right = index;
+        self.update_height(left);
+        left
+    }
+
+    // Restores the AVL invariant at `index`, returning the new root of its subtree.
+    fn rebalance(&mut self, index: u32) -> u32 {
+        self.update_height(index);
+
+        let (left, right) = self.children(index);
+        match Avl::rotation(
+            left,
+            right,
+            |link| self.height(link),
+            |link| self.children(link),
+        ) {
+            Rotation::None => index,
+            Rotation::Left => self.rotate_left(index),
+            Rotation::Right => self.rotate_right(index),
+            Rotation::RightLeft => {
+                let right = self.rotate_right(right);
+                self.node_mut(index).right = right;
+                self.rotate_left(index)
+            }
+            Rotation::LeftRight => {
+                let left = self.rotate_left(left);
+                self.node_mut(index).left = left;
+                self.rotate_right(index)
+            }
+        }
+    }
+
+    fn insert_aux(&mut self, node: u32, index: u32, val: T) -> u32 {
+        if node == NIL {
+            return self.alloc(val);
+        }
+
+        let x = self.node_mut(node);
+        if index <= x.num_to_left {
+            x.num_to_left += 1;
+            let left = x.left;
+            let left = self.insert_aux(left, index, val);
+            self.node_mut(node).left = left;
+        } else {
+            let (index, right) = (index - x.num_to_left - 1, x.right);
+            let right = self.insert_aux(right, index, val);
+            self.node_mut(node).right = right;
+        }
+        self.rebalance(node)
+    }
+
+    fn remove_aux(&mut self, node: u32, index: u32) -> (u32, T) {
+        let x = self.node_mut(node);
+        match index.cmp(&x.num_to_left) {
+            Ordering::Less => {
+                x.num_to_left -= 1;
+                let left = x.left;
+                let (left, res) = self.remove_aux(left, index);
+                self.node_mut(node).left = left;
+                (self.rebalance(node), res)
+            }
+            Ordering::Greater => {
+                let (index, right) = (index - x.num_to_left - 1, x.right);
+                let (right, res) = self.remove_aux(right, index);
+                self.node_mut(node).right = right;
+                (self.rebalance(node), res)
+            }
+            Ordering::Equal => match (x.left, x.right) {
+                (left, NIL) => (left, self.dealloc(node)),
+                (NIL, right) => (right, self.dealloc(node)),
+                (_, right) => {
+                    let (right, succ) = self.remove_aux(right, 0);
+                    let x = self.node_mut(node);
+                    x.right = right;
+                    let res = std::mem::replace(&mut x.val, succ);
+                    (self.rebalance(node), res)
+                }
+            },
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.size {
+            None
+        } else {
+            let mut index = index as u32;
+            let mut node = self.node(self.root);
+
+            loop {
+                match index.cmp(&node.num_to_left) {
+                    Ordering::Less => node = self.node(node.left),
+                    Ordering::Greater => {
+                        index -= node.num_to_left + 1;
+                        node = self.node(node.right);
+                    }
+                    Ordering::Equal => break Some(&node.val),
+                }
+            }
+        }
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.size {
+            None
+        } else {
+            let mut index = index as u32;
+            let mut curr = self.root;
+
+            loop {
+                let node = self.node(curr);
+                match index.cmp(&node.num_to_left) {
+                    Ordering::Less => curr = node.left,
+                    Ordering::Greater => {
+                        index -= node.num_to_left + 1;
+                        curr = node.right;
+                    }
+                    Ordering::Equal => break Some(&mut self.node_mut(curr).val),
+                }
+            }
+        }
+    }
+
+    pub fn push_front(&mut self, val: T) {
+        self.insert(0, val);
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+
+    pub fn push_back(&mut self, val: T) {
+        self.insert(self.size, val);
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(self.size - 1))
+        }
+    }
+
+    pub fn insert(&mut self, index: usize, val: T) {
+        if index > self.size {
+            panic!("Index out of bounds!");
+        } else {
+            self.size += 1;
+            self.root = self.insert_aux(self.root, index as u32, val);
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        if index >= self.size {
+            panic!("Index out of bounds!");
+        } else {
+            self.size -= 1;
+            let (root, res) = self.remove_aux(self.root, index as u32);
+            self.root = root;
+            res
+        }
+    }
+
+    // Frees every node at once. If `T` needs no drop, this takes constant time.
+    pub fn clear(&mut self) {
+        self.size = 0;
+        self.root = NIL;
+        self.free = NIL;
+        self.nodes.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut iter = Iter {
+            list: self,
+            stack: Vec::new(),
+        };
+        iter.push_left(self.root);
+        iter
+    }
+}
+
+pub struct Iter<'a, T> {
+    list: &'a ArenaTreeList<T>,
+    stack: Vec<&'a TreeNode<T>>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn push_left(&mut self, mut curr: u32) {
+        while curr != NIL {
+            let node = self.list.node(curr);
+            self.stack.push(node);
+            curr = node.left;
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.stack.pop();
+
+        next.map(|node| {
+            self.push_left(node.right);
+            &node.val
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArenaTreeList, NIL};
+    use crate::conformance::{self, impl_test_list};
+
+    // Returns the size and height of the subtree at `index`, asserting that every node's
+    // `num_to_left` and `height` are accurate and that the AVL invariant holds.
+    fn check_node<T>(tree: &ArenaTreeList<T>, index: u32) -> (u32, u8) {
+        if index == NIL {
+            (0, 0)
+        } else {
+            let node = tree.node(index);
+            let (left, hl) = check_node(tree, node.left);
+            let (right, hr) = check_node(tree, node.right);
+
+            assert_eq!(node.num_to_left, left);
+            assert_eq!(node.height, 1 + std::cmp::max(hl, hr));
+            assert!(hl <= hr + 1 && hr <= hl + 1);
+            (left + right + 1, node.height)
+        }
+    }
+
+    fn check<T>(tree: &ArenaTreeList<T>) {
+        assert_eq!(check_node(tree, tree.root).0 as usize, tree.len());
+    }
+
+    impl_test_list!(ArenaTreeList<usize>, ArenaTreeList::new(), check);
+
+    #[test]
+    fn test_conformance() {
+        conformance::run::<ArenaTreeList<usize>>();
+    }
+
+    #[test]
+    fn test_reuses_slots() {
+        let mut tree: ArenaTreeList<usize> = ArenaTreeList::with_capacity(100);
+        for i in 0..100 {
+            tree.push_back(i);
+        }
+
+        for i in 0..50 {
+            tree.remove(i);
+        }
+        for i in 0..50 {
+            tree.push_front(i);
+        }
+
+        check(&tree);
+        assert_eq!(tree.nodes.len(), 100);
+        assert_eq!(tree.nodes.capacity(), 100);
+    }
+
+    #[test]
+    fn test_get_mut_clear() {
+        let mut tree: ArenaTreeList<String> = ArenaTreeList::new();
+        for i in 0..10 {
+            tree.push_back(i.to_string());
+        }
+
+        tree.get_mut(3).unwrap().push('!');
+        assert_eq!(tree.get(3).map(String::as_str), Some("3!"));
+
+        tree.clear();
+        assert!(tree.is_empty());
+        assert_eq!(tree.get(0), None);
+
+        tree.push_back("a".to_string());
+        check(&tree);
+        assert_eq!(tree.get(0).map(String::as_str), Some("a"));
+    }
+}
@@ -0,0 +1,492 @@
+use crate::balance::{Avl, BalancePolicy};
+pub use crate::monoid::Monoid;
+pub use crate::node::Iter;
+use crate::node::{locate, Link, Position, TreeNode};
+use crate::tree_list::{append_tree, insert_into, remove_from, resolve_range, split_tree};
+use std::fmt;
+use std::ops::{Add, Deref, DerefMut, RangeBounds};
+
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Sum;
+
+impl<T: Copy + Default + Add<Output = T>> Monoid<T> for Sum {
+    type Summary = T;
+
+    fn identity() -> T {
+        T::default()
+    }
+
+    fn measure(val: &T) -> T {
+        *val
+    }
+
+    fn combine(left: &T, right: &T) -> T {
+        *left + *right
+    }
+}
+
+/// The summary of an empty range is `None`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Min;
+
+impl<T: Ord + Clone> Monoid<T> for Min {
+    type Summary = Option<T>;
+
+    fn identity() -> Option<T> {
+        None
+    }
+
+    fn measure(val: &T) -> Option<T> {
+        Some(val.clone())
+    }
+
+    fn combine(left: &Option<T>, right: &Option<T>) -> Option<T> {
+        match (left, right) {
+            (Some(left), Some(right)) => Some(std::cmp::min(left, right).clone()),
+            (left, None) => left.clone(),
+            (None, right) => right.clone(),
+        }
+    }
+}
+
+/// The summary of an empty range is `None`.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Max;
+
+impl<T: Ord + Clone> Monoid<T> for Max {
+    type Summary = Option<T>;
+
+    fn identity() -> Option<T> {
+        None
+    }
+
+    fn measure(val: &T) -> Option<T> {
+        Some(val.clone())
+    }
+
+    fn combine(left: &Option<T>, right: &Option<T>) -> Option<T> {
+        match (left, right) {
+            (Some(left), Some(right)) => Some(std::cmp::max(left, right).clone()),
+            (left, None) => left.clone(),
+            (None, right) => right.clone(),
+        }
+    }
+}
+
+// Every node caches the summary of its whole subtree, which the balance policies keep up to date
+// as they restructure the tree.
+pub struct AugmentedTreeList<T, M: Monoid<T>, P: BalancePolicy = Avl> {
+    pub(crate) root: Link<T, P, M>,
+    size: usize,
+}
+
+impl<T, M: Monoid<T>> AugmentedTreeList<T, M> {
+    pub fn new() -> Self {
+        Self::with_policy()
+    }
+}
+
+impl<T, M: Monoid<T>, P: BalancePolicy> Default for AugmentedTreeList<T, M, P> {
+    fn default() -> Self {
+        Self::with_policy()
+    }
+}
+
+// Recomputes the summaries along the path to `index`, after the element there has changed.
+fn refresh<T, P: BalancePolicy, M: Monoid<T>>(node: &mut TreeNode<T, P, M>, index: usize) {
+    match locate(index, node.num_to_left) {
+        Position::Left(index) => refresh(node.left.as_mut().unwrap(), index),
+        Position::Right(index) => refresh(node.right.as_mut().unwrap(), index),
+        Position::Here => {}
+    }
+    node.update_summary();
+}
+
+// Folds the elements in `start..end` of the subtree `link`, which holds `size` elements.
+// Subtrees entirely inside the range contribute their cached summary, so at most two paths
+// are walked.
+fn fold_aux<T, P: BalancePolicy, M: Monoid<T>>(
+    link: &Link<T, P, M>,
+    size: usize,
+    start: usize,
+    end: usize,
+) -> M::Summary {
+    match link {
+        None => M::identity(),
+        Some(_) if start >= end => M::identity(),
+        Some(node) if start == 0 && end == size => node.summary.clone(),
+        Some(node) => {
+            let num_to_left = node.num_to_left;
+            let mut res = M::identity();
+            if start < num_to_left {
+                let end = std::cmp::min(end, num_to_left);
+                res = fold_aux(&node.left, num_to_left, start, end);
+            }
+            if start <= num_to_left && num_to_left < end {
+                res = M::combine(&res, &M::measure(&node.val));
+            }
+            if end > num_to_left + 1 {
+                let start = start.saturating_sub(num_to_left + 1);
+                let right = fold_aux(
+                    &node.right,
+                    size - num_to_left - 1,
+                    start,
+                    end - num_to_left - 1,
+                );
+                res = M::combine(&res, &right);
+            }
+            res
+        }
+    }
+}
+
+impl<T, M: Monoid<T>, P: BalancePolicy> AugmentedTreeList<T, M, P> {
+    pub fn with_policy() -> Self {
+        AugmentedTreeList {
+            root: None,
+            size: 0,
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if index >= self.size {
+            None
+        } else {
+            Some(TreeNode::find(self.root.as_ref().unwrap(), index))
+        }
+    }
+
+    // The cached summaries can't be kept right through a plain `&mut T`, so this hands out a
+    // guard instead, which refreshes them along the path to the element when it is dropped.
+    pub fn get_mut(&mut self, index: usize) -> Option<ValueMut<'_, T, M, P>> {
+        if index >= self.size {
+            None
+        } else {
+            // Nothing may be left pending above the element while the guard is alive.
+            TreeNode::find_mut(self.root.as_mut().unwrap(), index);
+            Some(ValueMut { list: self, index })
+        }
+    }
+
+    pub fn push_front(&mut self, val: T) {
+        self.insert(0, val);
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+
+    pub fn push_back(&mut self, val: T) {
+        self.insert(self.size, val);
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(self.size - 1))
+        }
+    }
+
+    pub fn insert(&mut self, index: usize, val: T) {
+        insert_into(&mut self.root, &mut self.size, index, val);
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        remove_from(&mut self.root, &mut self.size, index)
+    }
+
+    pub fn split_off(&mut self, at: usize) -> AugmentedTreeList<T, M, P> {
+        let size = self.size;
+        let root = split_tree(&mut self.root, &mut self.size, at);
+        AugmentedTreeList {
+            root,
+            size: size - at,
+        }
+    }
+
+    pub fn append(&mut self, other: &mut AugmentedTreeList<T, M, P>) {
+        append_tree(
+            &mut self.root,
+            &mut self.size,
+            &mut other.root,
+            &mut other.size,
+        );
+    }
+
+    /// Combines the summaries of the elements in `range`, in order.
+    pub fn fold<R: RangeBounds<usize>>(&self, range: R) -> M::Summary {
+        let (start, end) = resolve_range(range, self.size);
+        fold_aux(&self.root, self.size, start, end)
+    }
+
+    /// Finds the first index at which `pred` holds for the summary of every element up to and
+    /// including that one, or `None` if it never does. `pred` must be monotone: once it holds for
+    /// some prefix, it holds for every longer one.
+    pub fn find_prefix<F: Fn(&M::Summary) -> bool>(&self, pred: F) -> Option<usize> {
+        let mut prefix = M::identity();
+        let mut curr = &self.root;
+        let mut index = 0;
+
+        while let Some(node) = curr {
+            let left = M::combine(&prefix, &TreeNode::summary_of(&node.left));
+            if node.left.is_some() && pred(&left) {
+                curr = &node.left;
+                continue;
+            }
+
+            let mid = M::combine(&left, &M::measure(&node.val));
+            if pred(&mid) {
+                return Some(index + node.num_to_left);
+            }
+            prefix = mid;
+            index += node.num_to_left + 1;
+            curr = &node.right;
+        }
+        None
+    }
+
+    /// The summary of the whole list.
+    pub fn summary(&self) -> M::Summary {
+        TreeNode::summary_of(&self.root)
+    }
+
+    pub fn clear(&mut self) {
+        self.size = 0;
+        TreeNode::free(self.root.take());
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    pub fn iter(&self) -> Iter<'_, T, P, M> {
+        Iter::new(&self.root, self.size)
+    }
+}
+
+impl<T: fmt::Debug, M: Monoid<T>, P: BalancePolicy> fmt::Debug for AugmentedTreeList<T, M, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+impl<T, M: Monoid<T>, P: BalancePolicy> Drop for AugmentedTreeList<T, M, P> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+// Every access goes back through the list, so each dereference costs `O(log n)`.
+pub struct ValueMut<'a, T, M: Monoid<T>, P: BalancePolicy = Avl> {
+    list: &'a mut AugmentedTreeList<T, M, P>,
+    index: usize,
+}
+
+impl<'a, T, M: Monoid<T>, P: BalancePolicy> Deref for ValueMut<'a, T, M, P> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        self.list.get(self.index).unwrap()
+    }
+}
+
+impl<'a, T, M: Monoid<T>, P: BalancePolicy> DerefMut for ValueMut<'a, T, M, P> {
+    fn deref_mut(&mut self) -> &mut T {
+        TreeNode::find_mut(self.list.root.as_mut().unwrap(), self.index)
+    }
+}
+
+impl<'a, T, M: Monoid<T>, P: BalancePolicy> Drop for ValueMut<'a, T, M, P> {
+    fn drop(&mut self) {
+        refresh(self.list.root.as_mut().unwrap(), self.index);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AugmentedTreeList, Max, Min, Monoid, Sum};
+    use crate::balance::tests::Validate;
+    use crate::balance::{Avl, RedBlack, Unbalanced, WeightBalanced};
+    use crate::conformance::{self, impl_test_list};
+    use crate::node::Link;
+
+    // Returns the summary of the subtree at `link`, asserting that every cached one is accurate.
+    fn summaries<T, P: Validate, M>(link: &Link<T, P, M>) -> M::Summary
+    where
+        M: Monoid<T>,
+        M::Summary: PartialEq + std::fmt::Debug,
+    {
+        match link {
+            None => M::identity(),
+            Some(node) => {
+                let left = M::combine(&summaries(&node.left), &M::measure(&node.val));
+                let summary = M::combine(&left, &summaries(&node.right));
+                assert_eq!(node.summary, summary);
+                summary
+            }
+        }
+    }
+
+    fn check<T, M, P: Validate>(tree: &AugmentedTreeList<T, M, P>)
+    where
+        M: Monoid<T>,
+        M::Summary: PartialEq + std::fmt::Debug,
+    {
+        assert_eq!(P::validate(&tree.root), tree.len());
+        summaries(&tree.root);
+    }
+
+    impl_test_list!(
+        AugmentedTreeList<usize, Sum, Unbalanced>,
+        AugmentedTreeList::with_policy(),
+        check
+    );
+    impl_test_list!(AugmentedTreeList<usize, Sum, Avl>, AugmentedTreeList::new(), check);
+    impl_test_list!(
+        AugmentedTreeList<usize, Sum, WeightBalanced>,
+        AugmentedTreeList::with_policy(),
+        check
+    );
+    impl_test_list!(
+        AugmentedTreeList<usize, Sum, RedBlack>,
+        AugmentedTreeList::with_policy(),
+        check
+    );
+
+    #[test]
+    fn test_conformance_policies() {
+        conformance::run::<AugmentedTreeList<usize, Sum, Unbalanced>>();
+        conformance::run::<AugmentedTreeList<usize, Sum, Avl>>();
+        conformance::run::<AugmentedTreeList<usize, Sum, WeightBalanced>>();
+        conformance::run::<AugmentedTreeList<usize, Sum, RedBlack>>();
+    }
+
+    fn fold_random<P: Validate>() {
+        let mut tree: AugmentedTreeList<usize, Sum, P> = AugmentedTreeList::with_policy();
+        let mut mins: AugmentedTreeList<usize, Min, P> = AugmentedTreeList::with_policy();
+        let mut maxes: AugmentedTreeList<usize, Max, P> = AugmentedTreeList::with_policy();
+        let mut vec: Vec<usize> = Vec::new();
+
+        for i in 0..500 {
+            let index = (i * 7919) % (vec.len() + 1);
+            let val = (i * 104_729) % 1000;
+            tree.insert(index, val);
+            mins.insert(index, val);
+            maxes.insert(index, val);
+            vec.insert(index, val);
+        }
+        for i in 0..200 {
+            let index = (i * 7919) % vec.len();
+            assert_eq!(tree.remove(index), vec.remove(index));
+            mins.remove(index);
+            maxes.remove(index);
+        }
+        check(&tree);
+        check(&mins);
+        check(&maxes);
+
+        for start in (0..vec.len()).step_by(7) {
+            for end in (start..=vec.len()).step_by(5) {
+                assert_eq!(tree.fold(start..end), vec[start..end].iter().sum::<usize>());
+                assert_eq!(mins.fold(start..end), vec[start..end].iter().min().copied());
+                assert_eq!(
+                    maxes.fold(start..end),
+                    vec[start..end].iter().max().copied()
+                );
+            }
+        }
+        assert_eq!(tree.fold(..=10), vec[..=10].iter().sum::<usize>());
+    }
+
+    #[test]
+    fn test_fold_random_policies() {
+        fold_random::<Unbalanced>();
+        fold_random::<Avl>();
+        fold_random::<WeightBalanced>();
+        fold_random::<RedBlack>();
+    }
+
+    fn split_append<P: Validate>() {
+        for &size in [0, 1, 2, 7, 100].iter() {
+            for at in 0..=size {
+                let mut tree: AugmentedTreeList<usize, Sum, P> = AugmentedTreeList::with_policy();
+                for i in 0..size {
+                    tree.push_back(i);
+                }
+
+                let mut other = tree.split_off(at);
+                check(&tree);
+                check(&other);
+                assert_eq!(tree.summary(), (0..at).sum::<usize>());
+                assert_eq!(other.summary(), (at..size).sum::<usize>());
+
+                tree.append(&mut other);
+                check(&tree);
+                assert!(other.is_empty());
+                assert_eq!(
+                    tree.iter().copied().collect::<Vec<usize>>(),
+                    (0..size).collect::<Vec<usize>>()
+                );
+                assert_eq!(tree.summary(), (0..size).sum::<usize>());
+            }
+        }
+    }
+
+    #[test]
+    fn test_split_append_policies() {
+        split_append::<Unbalanced>();
+        split_append::<Avl>();
+        split_append::<WeightBalanced>();
+        split_append::<RedBlack>();
+    }
+
+    #[test]
+    fn test_get_mut_refreshes() {
+        let mut tree: AugmentedTreeList<i64, Sum> = AugmentedTreeList::new();
+        for i in 0..100 {
+            tree.push_back(i);
+        }
+
+        *tree.get_mut(40).unwrap() = 1000;
+        {
+            let mut val = tree.get_mut(99).unwrap();
+            *val -= 99;
+            assert_eq!(*val, 0);
+        }
+
+        check(&tree);
+        assert!(tree.get_mut(100).is_none());
+        assert_eq!(tree.summary(), 4950 - 40 + 1000 - 99);
+        assert_eq!(tree.fold(40..41), 1000);
+    }
+
+    #[test]
+    fn test_find_prefix() {
+        let mut tree: AugmentedTreeList<usize, Sum> = AugmentedTreeList::new();
+        for i in 0..100 {
+            tree.push_back(i % 3);
+        }
+
+        for target in 0..tree.summary() + 2 {
+            let expected = (0..100).find(|&i| tree.fold(..=i) >= target);
+            assert_eq!(tree.find_prefix(|&sum| sum >= target), expected);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_fold_out_of_bounds() {
+        let mut tree: AugmentedTreeList<usize, Sum> = AugmentedTreeList::new();
+        tree.push_back(1);
+        tree.fold(0..2);
+    }
+}
@@ -0,0 +1,706 @@
+use crate::monoid::Monoid;
+use crate::node::{Link, TreeNode};
+use std::fmt::Debug;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A strategy for keeping the tree behind a list balanced.
+///
+/// Every node carries a `Meta` value owned by the policy. After an insertion or deletion, the
+/// list calls `rebalance` on each node along the path back up to the root, so a policy only ever
+/// has to repair one node whose subtrees are already valid. `join` concatenates two trees around
+/// a single node, which is what lets lists be split and appended without touching every element.
+/// The remaining hooks default to doing nothing and exist for top-down schemes such as `RedBlack`.
+///
+/// The policies shipped with this crate are `Unbalanced`, `Avl`, `WeightBalanced` and `RedBlack`.
+/// The trait is sealed: its methods work on the crate's private node type, so it can't be
+/// implemented outside this crate.
+pub trait BalancePolicy: sealed::Sealed + Sized {
+    type Meta: Copy + Debug;
+
+    /// The metadata of a freshly inserted leaf.
+    fn new_meta() -> Self::Meta;
+
+    /// Repairs `node` after one of its subtrees has changed, returning the new subtree root.
+    fn rebalance<T, M: Monoid<T>>(node: Box<TreeNode<T, Self, M>>) -> Box<TreeNode<T, Self, M>>;
+
+    /// Concatenates `left`, the childless node `mid` and `right` into one balanced tree, given
+    /// that `left` holds `left_size` elements. The roots of `left` and `right` need only satisfy
+    /// the invariants that hold for any subtree.
+    fn join<T, M: Monoid<T>>(
+        left: Link<T, Self, M>,
+        left_size: usize,
+        mid: Box<TreeNode<T, Self, M>>,
+        right: Link<T, Self, M>,
+    ) -> Box<TreeNode<T, Self, M>>;
+
+    /// Builds a balanced tree from the next `len` elements of `iter` in `O(len)` time. The
+    /// default splits the elements evenly around a middle node at every level and lets
+    /// `rebalance` fill in each node's metadata, which suits any policy that can describe a
+    /// perfectly balanced tree. `iter` must yield at least `len` elements.
+    fn build<T, M: Monoid<T>, I: Iterator<Item = T>>(iter: &mut I, len: usize) -> Link<T, Self, M> {
+        if len == 0 {
+            None
+        } else {
+            let left_size = len / 2;
+            let left = Self::build(iter, left_size);
+            let mid = Box::new(TreeNode::new(iter.next().unwrap()));
+            let right = Self::build(iter, len - left_size - 1);
+            Some(Self::rebalance(TreeNode::attach(
+                mid, left, left_size, right,
+            )))
+        }
+    }
+
+    /// Called before a deletion descends into the (non-empty) left subtree of `node`.
+    fn descend_left<T, M: Monoid<T>>(node: Box<TreeNode<T, Self, M>>) -> Box<TreeNode<T, Self, M>> {
+        node
+    }
+
+    /// Called before a deletion descends to the right of `node`, or removes `node` itself.
+    fn descend_right<T, M: Monoid<T>>(
+        node: Box<TreeNode<T, Self, M>>,
+    ) -> Box<TreeNode<T, Self, M>> {
+        node
+    }
+
+    /// Called on the root before a deletion starts.
+    fn begin_remove<T, M: Monoid<T>>(root: Box<TreeNode<T, Self, M>>) -> Box<TreeNode<T, Self, M>> {
+        root
+    }
+
+    /// Called on the root once an insertion or deletion has finished.
+    fn fix_root<T, M: Monoid<T>>(root: Box<TreeNode<T, Self, M>>) -> Box<TreeNode<T, Self, M>> {
+        root
+    }
+}
+
+/// Never rotates. Insertion and deletion are as cheap as possible, but sequential insertion
+/// degrades the tree into a linked list.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Unbalanced;
+
+impl sealed::Sealed for Unbalanced {}
+
+impl BalancePolicy for Unbalanced {
+    type Meta = ();
+
+    fn new_meta() {}
+
+    fn rebalance<T, M: Monoid<T>>(
+        mut node: Box<TreeNode<T, Self, M>>,
+    ) -> Box<TreeNode<T, Self, M>> {
+        node.update_summary();
+        node
+    }
+
+    fn join<T, M: Monoid<T>>(
+        left: Link<T, Self, M>,
+        left_size: usize,
+        mid: Box<TreeNode<T, Self, M>>,
+        right: Link<T, Self, M>,
+    ) -> Box<TreeNode<T, Self, M>> {
+        TreeNode::attach(mid, left, left_size, right)
+    }
+}
+
+/// Keeps the heights of every node's subtrees within one of each other. This gives the
+/// shallowest trees, and so the fastest lookups, at the cost of more rotations.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Avl;
+
+impl Avl {
+    fn height<T, M: Monoid<T>>(link: &Link<T, Self, M>) -> usize {
+        link.as_ref().map_or(0, |node| node.meta)
+    }
+
+    fn children<T, M: Monoid<T>>(
+        link: &Link<T, Self, M>,
+    ) -> (&Link<T, Self, M>, &Link<T, Self, M>) {
+        let node = link.as_ref().unwrap();
+        (&node.left, &node.right)
+    }
+
+    fn update<T, M: Monoid<T>>(node: &mut TreeNode<T, Self, M>) {
+        node.meta = Self::parent_height(Self::height(&node.left), Self::height(&node.right));
+    }
+
+    fn rotate_left<T, M: Monoid<T>>(node: Box<TreeNode<T, Self, M>>) -> Box<TreeNode<T, Self, M>> {
+        let mut root = TreeNode::rotate_left(node);
+        Self::update(root.left.as_mut().unwrap());
+        Self::update(&mut root);
+        root
+    }
+
+    fn rotate_right<T, M: Monoid<T>>(node: Box<TreeNode<T, Self, M>>) -> Box<TreeNode<T, Self, M>> {
+        let mut root = TreeNode::rotate_right(node);
+        Self::update(root.right.as_mut().unwrap());
+        Self::update(&mut root);
+        root
+    }
+}
+
+// How a node is rotated to restore the AVL invariant, as decided by `Avl::rotation`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Rotation {
+    None,
+    Left,
+    Right,
+    // The right child is rotated right first.
+    RightLeft,
+    // The left child is rotated left first.
+    LeftRight,
+}
+
+// These look only at heights, so that lists with node layouts of their own can keep the same
+// balance as `Avl`. A link of type `L` is passed to `height` and, for the taller child alone,
+// to `children`.
+impl Avl {
+    pub(crate) fn parent_height(left: usize, right: usize) -> usize {
+        1 + std::cmp::max(left, right)
+    }
+
+    pub(crate) fn rotation<L, H, C>(left: L, right: L, height: H, children: C) -> Rotation
+    where
+        L: Copy,
+        H: Fn(L) -> usize,
+        C: FnOnce(L) -> (L, L),
+    {
+        let (hl, hr) = (height(left), height(right));
+        if hl > hr + 1 {
+            let (inner_left, inner_right) = children(left);
+            if height(inner_left) < height(inner_right) {
+                Rotation::LeftRight
+            } else {
+                Rotation::Right
+            }
+        } else if hr > hl + 1 {
+            let (inner_left, inner_right) = children(right);
+            if height(inner_right) < height(inner_left) {
+                Rotation::RightLeft
+            } else {
+                Rotation::Left
+            }
+        } else {
+            Rotation::None
+        }
+    }
+}
+
+impl sealed::Sealed for Avl {}
+
+impl BalancePolicy for Avl {
+    type Meta = usize;
+
+    fn new_meta() -> usize {
+        1
+    }
+
+    fn rebalance<T, M: Monoid<T>>(
+        mut node: Box<TreeNode<T, Self, M>>,
+    ) -> Box<TreeNode<T, Self, M>> {
+        Self::update(&mut node);
+        node.update_summary();
+
+        match Self::rotation(&node.left, &node.right, Self::height, Self::children) {
+            Rotation::None => node,
+            Rotation::Left => Self::rotate_left(node),
+            Rotation::Right => Self::rotate_right(node),
+            Rotation::RightLeft => {
+                node.right = node.right.take().map(Self::rotate_right);
+                Self::rotate_left(node)
+            }
+            Rotation::LeftRight => {
+                node.left = node.left.take().map(Self::rotate_left);
+                Self::rotate_right(node)
+            }
+        }
+    }
+
+    fn join<T, M: Monoid<T>>(
+        left: Link<T, Self, M>,
+        left_size: usize,
+        mid: Box<TreeNode<T, Self, M>>,
+        right: Link<T, Self, M>,
+    ) -> Box<TreeNode<T, Self, M>> {
+        let (hl, hr) = (Self::height(&left), Self::height(&right));
+        if hl > hr + 1 {
+            let fits = |node: &TreeNode<T, Self, M>| node.meta <= hr + 1;
+            TreeNode::join_right(left.unwrap(), left_size, mid, right, &fits)
+        } else if hr > hl + 1 {
+            let fits = |node: &TreeNode<T, Self, M>| node.meta <= hl + 1;
+            TreeNode::join_left(left, left_size, mid, right.unwrap(), &fits)
+        } else {
+            Self::rebalance(TreeNode::attach(mid, left, left_size, right))
+        }
+    }
+}
+
+/// Keeps the sizes of every node's subtrees within a constant factor of each other, as in
+/// Adams' weight-balanced trees with the parameters proposed by Hirai and Yamamoto. The trees
+/// are somewhat deeper than AVL trees, but rotations are rarer.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WeightBalanced;
+
+impl WeightBalanced {
+    const DELTA: usize = 3;
+    const GAMMA: usize = 2;
+
+    fn weight<T, M: Monoid<T>>(link: &Link<T, Self, M>) -> usize {
+        link.as_ref().map_or(0, |node| node.meta) + 1
+    }
+
+    fn update<T, M: Monoid<T>>(node: &mut TreeNode<T, Self, M>) {
+        node.meta = Self::weight(&node.left) + Self::weight(&node.right) - 1;
+    }
+
+    fn rotate_left<T, M: Monoid<T>>(node: Box<TreeNode<T, Self, M>>) -> Box<TreeNode<T, Self, M>> {
+        let mut root = TreeNode::rotate_left(node);
+        Self::update(root.left.as_mut().unwrap());
+        Self::update(&mut root);
+        root
+    }
+
+    fn rotate_right<T, M: Monoid<T>>(node: Box<TreeNode<T, Self, M>>) -> Box<TreeNode<T, Self, M>> {
+        let mut root = TreeNode::rotate_right(node);
+        Self::update(root.right.as_mut().unwrap());
+        Self::update(&mut root);
+        root
+    }
+}
+
+impl sealed::Sealed for WeightBalanced {}
+
+impl BalancePolicy for WeightBalanced {
+    type Meta = usize;
+
+    fn new_meta() -> usize {
+        1
+    }
+
+    fn rebalance<T, M: Monoid<T>>(
+        mut node: Box<TreeNode<T, Self, M>>,
+    ) -> Box<TreeNode<T, Self, M>> {
+        Self::update(&mut node);
+        node.update_summary();
+
+        let left = Self::weight(&node.left);
+        let right = Self::weight(&node.right);
+        if left > Self::DELTA * right {
+            let child = node.left.take().unwrap();
+            node.left = if Self::weight(&child.right) >= Self::GAMMA * Self::weight(&child.left) {
+                Some(Self::rotate_left(child))
+            } else {
+                Some(child)
+            };
+            Self::rotate_right(node)
+        } else if right > Self::DELTA * left {
+            let child = node.right.take().unwrap();
+            node.right = if Self::weight(&child.left) >= Self::GAMMA * Self::weight(&child.right) {
+                Some(Self::rotate_right(child))
+            } else {
+                Some(child)
+            };
+            Self::rotate_left(node)
+        } else {
+            node
+        }
+    }
+
+    fn join<T, M: Monoid<T>>(
+        left: Link<T, Self, M>,
+        left_size: usize,
+        mid: Box<TreeNode<T, Self, M>>,
+        right: Link<T, Self, M>,
+    ) -> Box<TreeNode<T, Self, M>> {
+        let (wl, wr) = (Self::weight(&left), Self::weight(&right));
+        if wl > Self::DELTA * wr {
+            let fits = |node: &TreeNode<T, Self, M>| node.meta < Self::DELTA * wr;
+            TreeNode::join_right(left.unwrap(), left_size, mid, right, &fits)
+        } else if wr > Self::DELTA * wl {
+            let fits = |node: &TreeNode<T, Self, M>| node.meta < Self::DELTA * wl;
+            TreeNode::join_left(left, left_size, mid, right.unwrap(), &fits)
+        } else {
+            Self::rebalance(TreeNode::attach(mid, left, left_size, right))
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Color {
+    Red,
+    Black,
+}
+
+impl Color {
+    fn flip(self) -> Self {
+        match self {
+            Color::Red => Color::Black,
+            Color::Black => Color::Red,
+        }
+    }
+}
+
+/// A left-leaning red-black tree, following Sedgewick. Deletion restructures the tree on the
+/// way down as well as on the way up. Black heights are not stored, so joining trees has to
+/// measure them, and splitting or concatenating lists takes `O(log^2 n)` time.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct RedBlack;
+
+impl RedBlack {
+    fn is_red<T, M: Monoid<T>>(link: &Link<T, Self, M>) -> bool {
+        matches!(link, Some(node) if node.meta == Color::Red)
+    }
+
+    fn left_is_red<T, M: Monoid<T>>(link: &Link<T, Self, M>) -> bool {
+        matches!(link, Some(node) if Self::is_red(&node.left))
+    }
+
+    fn rotate_left<T, M: Monoid<T>>(node: Box<TreeNode<T, Self, M>>) -> Box<TreeNode<T, Self, M>> {
+        let color = node.meta;
+        let mut root = TreeNode::rotate_left(node);
+        root.left.as_mut().unwrap().meta = Color::Red;
+        root.meta = color;
+        root
+    }
+
+    fn rotate_right<T, M: Monoid<T>>(node: Box<TreeNode<T, Self, M>>) -> Box<TreeNode<T, Self, M>> {
+        let color = node.meta;
+        let mut root = TreeNode::rotate_right(node);
+        root.right.as_mut().unwrap().meta = Color::Red;
+        root.meta = color;
+        root
+    }
+
+    fn flip_colors<T, M: Monoid<T>>(node: &mut TreeNode<T, Self, M>) {
+        node.meta = node.meta.flip();
+        for child in node.left.iter_mut().chain(node.right.iter_mut()) {
+            child.meta = child.meta.flip();
+        }
+    }
+
+    fn black_height<T, M: Monoid<T>>(mut link: &Link<T, Self, M>) -> usize {
+        let mut height = 0;
+        while let Some(node) = link {
+            if node.meta == Color::Black {
+                height += 1;
+            }
+            link = &node.left;
+        }
+        height
+    }
+
+    // Builds the tree as a 2-3 tree with the given black height, splitting off 3-nodes (a black
+    // node with a red left child) only once two 2-node subtrees could no longer hold everything.
+    // A perfectly balanced shape doesn't work here, since it can need red right children.
+    fn build_aux<T, M: Monoid<T>, I: Iterator<Item = T>>(
+        iter: &mut I,
+        len: usize,
+        height: u32,
+    ) -> Link<T, Self, M> {
+        if len == 0 {
+            return None;
+        }
+
+        let max_child = 3usize.saturating_pow(height - 1) - 1;
+        if len <= 2 * max_child + 1 {
+            let left_size = len / 2;
+            let left = Self::build_aux(iter, left_size, height - 1);
+            let mut mid = Box::new(TreeNode::new(iter.next().unwrap()));
+            let right = Self::build_aux(iter, len - left_size - 1, height - 1);
+
+            mid.meta = Color::Black;
+            Some(TreeNode::attach(mid, left, left_size, right))
+        } else {
+            let sizes = [(len - 2) / 3, (len - 1) / 3, len / 3];
+            let left = Self::build_aux(iter, sizes[0], height - 1);
+            let red = Box::new(TreeNode::new(iter.next().unwrap()));
+            let mid = Self::build_aux(iter, sizes[1], height - 1);
+            let red = TreeNode::attach(red, left, sizes[0], mid);
+
+            let mut black = Box::new(TreeNode::new(iter.next().unwrap()));
+            let right = Self::build_aux(iter, sizes[2], height - 1);
+            black.meta = Color::Black;
+            Some(TreeNode::attach(
+                black,
+                Some(red),
+                sizes[0] + sizes[1] + 1,
+                right,
+            ))
+        }
+    }
+
+    fn blacken<T, M: Monoid<T>>(mut node: Box<TreeNode<T, Self, M>>) -> Box<TreeNode<T, Self, M>> {
+        node.meta = Color::Black;
+        node
+    }
+
+    // Like `TreeNode::join_right`, but descends until reaching a black node whose black height
+    // matches that of `right`, tracking black heights along the way.
+    fn join_right<T, M: Monoid<T>>(
+        mut left: Box<TreeNode<T, Self, M>>,
+        left_height: usize,
+        left_size: usize,
+        mid: Box<TreeNode<T, Self, M>>,
+        right: Link<T, Self, M>,
+        right_height: usize,
+    ) -> Box<TreeNode<T, Self, M>> {
+        M::push_down(&mut left);
+        let spine_size = left_size - left.num_to_left - 1;
+        let spine_height = match left.meta {
+            Color::Black => left_height - 1,
+            Color::Red => left_height,
+        };
+
+        left.right = Some(match left.right.take() {
+            Some(spine) if spine_height != right_height || spine.meta == Color::Red => {
+                let (height, size) = (spine_height, spine_size);
+                Self::join_right(spine, height, size, mid, right, right_height)
+            }
+            spine => TreeNode::attach(mid, spine, spine_size, right),
+        });
+        Self::rebalance(left)
+    }
+
+    fn join_left<T, M: Monoid<T>>(
+        left: Link<T, Self, M>,
+        left_height: usize,
+        left_size: usize,
+        mid: Box<TreeNode<T, Self, M>>,
+        mut right: Box<TreeNode<T, Self, M>>,
+        right_height: usize,
+    ) -> Box<TreeNode<T, Self, M>> {
+        M::push_down(&mut right);
+        right.num_to_left += left_size + 1;
+        let spine_height = match right.meta {
+            Color::Black => right_height - 1,
+            Color::Red => right_height,
+        };
+
+        right.left = Some(match right.left.take() {
+            Some(spine) if spine_height != left_height || spine.meta == Color::Red => {
+                Self::join_left(left, left_height, left_size, mid, spine, spine_height)
+            }
+            spine => TreeNode::attach(mid, left, left_size, spine),
+        });
+        Self::rebalance(right)
+    }
+
+    // Ensures that the left child or one of its children is red before descending left.
+    fn move_red_left<T, M: Monoid<T>>(
+        mut node: Box<TreeNode<T, Self, M>>,
+    ) -> Box<TreeNode<T, Self, M>> {
+        Self::flip_colors(&mut node);
+        if Self::left_is_red(&node.right) {
+            node.right = Some(Self::rotate_right(node.right.take().unwrap()));
+            node = Self::rotate_left(node);
+            Self::flip_colors(&mut node);
+        }
+        node
+    }
+
+    // Ensures that the right child or one of its children is red before descending right.
+    fn move_red_right<T, M: Monoid<T>>(
+        mut node: Box<TreeNode<T, Self, M>>,
+    ) -> Box<TreeNode<T, Self, M>> {
+        Self::flip_colors(&mut node);
+        if Self::left_is_red(&node.left) {
+            node = Self::rotate_right(node);
+            Self::flip_colors(&mut node);
+        }
+        node
+    }
+}
+
+impl sealed::Sealed for RedBlack {}
+
+impl BalancePolicy for RedBlack {
+    type Meta = Color;
+
+    fn new_meta() -> Color {
+        Color::Red
+    }
+
+    fn rebalance<T, M: Monoid<T>>(
+        mut node: Box<TreeNode<T, Self, M>>,
+    ) -> Box<TreeNode<T, Self, M>> {
+        node.update_summary();
+        if Self::is_red(&node.right) && !Self::is_red(&node.left) {
+            node = Self::rotate_left(node);
+        }
+        if Self::is_red(&node.left) && Self::left_is_red(&node.left) {
+            node = Self::rotate_right(node);
+        }
+        if Self::is_red(&node.left) && Self::is_red(&node.right) {
+            Self::flip_colors(&mut node);
+        }
+        node
+    }
+
+    fn join<T, M: Monoid<T>>(
+        left: Link<T, Self, M>,
+        left_size: usize,
+        mut mid: Box<TreeNode<T, Self, M>>,
+        right: Link<T, Self, M>,
+    ) -> Box<TreeNode<T, Self, M>> {
+        let left = left.map(Self::blacken);
+        let right = right.map(Self::blacken);
+        let (hl, hr) = (Self::black_height(&left), Self::black_height(&right));
+
+        mid.meta = Color::Red;
+        let root = if hl > hr {
+            Self::join_right(left.unwrap(), hl, left_size, mid, right, hr)
+        } else if hr > hl {
+            Self::join_left(left, hl, left_size, mid, right.unwrap(), hr)
+        } else {
+            TreeNode::attach(mid, left, left_size, right)
+        };
+        Self::blacken(root)
+    }
+
+    fn build<T, M: Monoid<T>, I: Iterator<Item = T>>(iter: &mut I, len: usize) -> Link<T, Self, M> {
+        // A 2-3 tree of black height `h` holds between `2^h - 1` and `3^h - 1` elements.
+        let height = (usize::BITS - 1) - (len + 1).leading_zeros();
+        Self::build_aux(iter, len, height)
+    }
+
+    fn descend_left<T, M: Monoid<T>>(node: Box<TreeNode<T, Self, M>>) -> Box<TreeNode<T, Self, M>> {
+        if !Self::is_red(&node.left) && !Self::left_is_red(&node.left) {
+            Self::move_red_left(node)
+        } else {
+            node
+        }
+    }
+
+    fn descend_right<T, M: Monoid<T>>(
+        mut node: Box<TreeNode<T, Self, M>>,
+    ) -> Box<TreeNode<T, Self, M>> {
+        if Self::is_red(&node.left) {
+            node = Self::rotate_right(node);
+        }
+        if node.right.is_some() && !Self::is_red(&node.right) && !Self::left_is_red(&node.right) {
+            node = Self::move_red_right(node);
+        }
+        node
+    }
+
+    // Deletion descends with a red link in hand, so the root borrows one if neither child has it.
+    fn begin_remove<T, M: Monoid<T>>(
+        mut root: Box<TreeNode<T, Self, M>>,
+    ) -> Box<TreeNode<T, Self, M>> {
+        if !Self::is_red(&root.left) && !Self::is_red(&root.right) {
+            root.meta = Color::Red;
+        }
+        root
+    }
+
+    fn fix_root<T, M: Monoid<T>>(mut root: Box<TreeNode<T, Self, M>>) -> Box<TreeNode<T, Self, M>> {
+        root.meta = Color::Black;
+        root
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::{Avl, BalancePolicy, Color, RedBlack, Unbalanced, WeightBalanced};
+    use crate::monoid::Monoid;
+    use crate::node::Link;
+
+    // Asserts the invariants of a policy over a whole tree, including the accuracy of every
+    // node's `num_to_left`, and returns the size of the tree.
+    pub(crate) trait Validate: BalancePolicy {
+        fn validate<T, M: Monoid<T>>(root: &Link<T, Self, M>) -> usize;
+    }
+
+    fn sizes<T, P: BalancePolicy, M: Monoid<T>>(link: &Link<T, P, M>) -> usize {
+        match link {
+            None => 0,
+            Some(node) => {
+                let left = sizes(&node.left);
+                assert_eq!(node.num_to_left, left);
+                left + sizes(&node.right) + 1
+            }
+        }
+    }
+
+    fn avl_height<T, M: Monoid<T>>(link: &Link<T, Avl, M>) -> usize {
+        match link {
+            None => 0,
+            Some(node) => {
+                let left = avl_height(&node.left);
+                let right = avl_height(&node.right);
+                assert!(left <= right + 1 && right <= left + 1);
+                assert_eq!(node.meta, 1 + std::cmp::max(left, right));
+                node.meta
+            }
+        }
+    }
+
+    fn weight<T, M: Monoid<T>>(link: &Link<T, WeightBalanced, M>) -> usize {
+        match link {
+            None => 1,
+            Some(node) => {
+                let left = weight(&node.left);
+                let right = weight(&node.right);
+                assert!(left <= WeightBalanced::DELTA * right);
+                assert!(right <= WeightBalanced::DELTA * left);
+                assert_eq!(node.meta, left + right - 1);
+                left + right
+            }
+        }
+    }
+
+    fn black_height<T, M: Monoid<T>>(link: &Link<T, RedBlack, M>) -> usize {
+        match link {
+            None => 0,
+            Some(node) => {
+                let left = black_height(&node.left);
+                assert_eq!(left, black_height(&node.right));
+                assert!(!RedBlack::is_red(&node.right));
+                assert!(node.meta == Color::Black || !RedBlack::is_red(&node.left));
+
+                if node.meta == Color::Black {
+                    left + 1
+                } else {
+                    left
+                }
+            }
+        }
+    }
+
+    impl Validate for Unbalanced {
+        fn validate<T, M: Monoid<T>>(root: &Link<T, Self, M>) -> usize {
+            sizes(root)
+        }
+    }
+
+    impl Validate for Avl {
+        fn validate<T, M: Monoid<T>>(root: &Link<T, Self, M>) -> usize {
+            avl_height(root);
+            sizes(root)
+        }
+    }
+
+    impl Validate for WeightBalanced {
+        fn validate<T, M: Monoid<T>>(root: &Link<T, Self, M>) -> usize {
+            weight(root);
+            sizes(root)
+        }
+    }
+
+    impl Validate for RedBlack {
+        fn validate<T, M: Monoid<T>>(root: &Link<T, Self, M>) -> usize {
+            assert!(!RedBlack::is_red(root));
+            black_height(root);
+            sizes(root)
+        }
+    }
+
+    pub(crate) fn depth<T, P: BalancePolicy, M: Monoid<T>>(link: &Link<T, P, M>) -> usize {
+        link.as_ref().map_or(0, |node| {
+            1 + std::cmp::max(depth(&node.left), depth(&node.right))
+        })
+    }
+}
@@ -0,0 +1,357 @@
+const MAX_CHILDREN: usize = 16;
+const MIN_CHILDREN: usize = MAX_CHILDREN / 2;
+
+// Leaves hold up to `B` elements contiguously. Internal nodes hold between `MIN_CHILDREN` and
+// `MAX_CHILDREN` children, each paired with the number of elements in its subtree, and every
+// leaf is at the same depth. Only the root may fall below the minimum occupancy.
+#[derive(Debug)]
+enum Node<T> {
+    Leaf(Vec<T>),
+    Internal(Vec<(usize, Node<T>)>),
+}
+
+impl<T> Node<T> {
+    fn len(&self) -> usize {
+        match self {
+            Node::Leaf(vals) => vals.len(),
+            Node::Internal(children) => children.iter().map(|child| child.0).sum(),
+        }
+    }
+
+    // Finds the child containing `index`, and the index within that child. An index one past
+    // the end belongs to the last child.
+    fn locate(children: &[(usize, Node<T>)], mut index: usize) -> (usize, usize) {
+        let last = children.len() - 1;
+        for (i, child) in children[..last].iter().enumerate() {
+            if index < child.0 {
+                return (i, index);
+            }
+            index -= child.0;
+        }
+        (last, index)
+    }
+}
+
+#[derive(Debug)]
+pub struct ChunkedTreeList<T, const B: usize = 64> {
+    root: Node<T>,
+    size: usize,
+}
+
+impl<T, const B: usize> Default for ChunkedTreeList<T, B> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const B: usize> ChunkedTreeList<T, B> {
+    pub fn new() -> Self {
+        assert!(B >= 2, "Chunks must hold at least two elements!");
+        ChunkedTreeList {
+            root: Node::Leaf(Vec::with_capacity(B)),
+            size: 0,
+        }
+    }
+
+    fn is_underfull(node: &Node<T>) -> bool {
+        match node {
+            Node::Leaf(vals) => vals.len() < B / 2,
+            Node::Internal(children) => children.len() < MIN_CHILDREN,
+        }
+    }
+
+    fn insert_aux(node: &mut Node<T>, index: usize, val: T) -> Option<Node<T>> {
+        match node {
+            Node::Leaf(vals) => {
+                if vals.len() < B {
+                    vals.insert(index, val);
+                    None
+                } else {
+                    let mut right = Vec::with_capacity(B);
+                    right.extend(vals.drain(B / 2..));
+                    if index <= B / 2 {
+                        vals.insert(index, val);
+                    } else {
+                        right.insert(index - B / 2, val);
+                    }
+                    Some(Node::Leaf(right))
+                }
+            }
+            Node::Internal(children) => {
+                let (i, index) = Node::locate(children, index);
+                children[i].0 += 1;
+
+                if let Some(split) = Self::insert_aux(&mut children[i].1, index, val) {
+                    let split_len = split.len();
+                    children[i].0 -= split_len;
+                    children.insert(i + 1, (split_len, split));
+
+                    if children.len() > MAX_CHILDREN {
+                        let right = children.split_off(children.len() / 2);
+                        return Some(Node::Internal(right));
+                    }
+                }
+                None
+            }
+        }
+    }
+
+    // Merges the child at `i` with a neighbour, splitting the result evenly again if it is too
+    // large to fit in one node.
+    fn fix_underfull(children: &mut Vec<(usize, Node<T>)>, i: usize) {
+        let i = if i + 1 < children.len() { i } else { i - 1 };
+        let (right_len, right) = children.remove(i + 1);
+        let (left_len, left) = &mut children[i];
+        *left_len += right_len;
+
+        let split = match (left, right) {
+            (Node::Leaf(left), Node::Leaf(right)) => {
+                left.extend(right);
+                if left.len() > B {
+                    let mut split = Vec::with_capacity(B);
+                    split.extend(left.drain(left.len() / 2..));
+                    Some(Node::Leaf(split))
+                } else {
+                    None
+                }
+            }
+            (Node::Internal(left), Node::Internal(right)) => {
+                left.extend(right);
+                if left.len() > MAX_CHILDREN {
+                    Some(Node::Internal(left.split_off(left.len() / 2)))
+                } else {
+                    None
+                }
+            }
+            _ => unreachable!(),
+        };
+
+        if let Some(split) = split {
+            let split_len = split.len();
+            children[i].0 -= split_len;
+            children.insert(i + 1, (split_len, split));
+        }
+    }
+
+    fn remove_aux(node: &mut Node<T>, index: usize) -> T {
+        match node {
+            Node::Leaf(vals) => vals.remove(index),
+            Node::Internal(children) => {
+                let (i, index) = Node::locate(children, index);
+                children[i].0 -= 1;
+
+                let res = Self::remove_aux(&mut children[i].1, index);
+                if Self::is_underfull(&children[i].1) && children.len() > 1 {
+                    Self::fix_underfull(children, i);
+                }
+                res
+            }
+        }
+    }
+
+    pub fn get(&self, mut index: usize) -> Option<&T> {
+        if index >= self.size {
+            None
+        } else {
+            let mut node = &self.root;
+
+            loop {
+                match node {
+                    Node::Leaf(vals) => break vals.get(index),
+                    Node::Internal(children) => {
+                        let (i, child_index) = Node::locate(children, index);
+                        node = &children[i].1;
+                        index = child_index;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn get_mut(&mut self, mut index: usize) -> Option<&mut T> {
+        if index >= self.size {
+            None
+        } else {
+            let mut node = &mut self.root;
+
+            loop {
+                match node {
+                    Node::Leaf(vals) => break vals.get_mut(index),
+                    Node::Internal(children) => {
+                        let (i, child_index) = Node::locate(children, index);
+                        node = &mut children[i].1;
+                        index = child_index;
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn push_front(&mut self, val: T) {
+        self.insert(0, val);
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+
+    pub fn push_back(&mut self, val: T) {
+        self.insert(self.size, val);
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(self.size - 1))
+        }
+    }
+
+    pub fn insert(&mut self, index: usize, val: T) {
+        if index > self.size {
+            panic!("Index out of bounds!");
+        } else {
+            self.size += 1;
+
+            if let Some(split) = Self::insert_aux(&mut self.root, index, val) {
+                let left = std::mem::replace(&mut self.root, Node::Internal(Vec::new()));
+                let children = vec![(left.len(), left), (split.len(), split)];
+                self.root = Node::Internal(children);
+            }
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        if index >= self.size {
+            panic!("Index out of bounds!");
+        } else {
+            self.size -= 1;
+            let res = Self::remove_aux(&mut self.root, index);
+
+            if let Node::Internal(children) = &mut self.root {
+                if children.len() == 1 {
+                    self.root = children.pop().unwrap().1;
+                }
+            }
+            res
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.size = 0;
+        self.root = Node::Leaf(Vec::with_capacity(B));
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        match &self.root {
+            Node::Leaf(vals) => Iter {
+                stack: Vec::new(),
+                leaf: vals.iter(),
+            },
+            Node::Internal(children) => Iter {
+                stack: vec![children.iter()],
+                leaf: [].iter(),
+            },
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    stack: Vec<std::slice::Iter<'a, (usize, Node<T>)>>,
+    leaf: std::slice::Iter<'a, T>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(val) = self.leaf.next() {
+                break Some(val);
+            }
+
+            match self.stack.last_mut()?.next() {
+                None => {
+                    self.stack.pop();
+                }
+                Some((_, Node::Leaf(vals))) => self.leaf = vals.iter(),
+                Some((_, Node::Internal(children))) => self.stack.push(children.iter()),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ChunkedTreeList, Node, MAX_CHILDREN, MIN_CHILDREN};
+    use crate::conformance::{self, impl_test_list};
+
+    // Returns the size and depth of `node`, asserting that the recorded child sizes are
+    // accurate, that nodes are neither over- nor underfull, and that all leaves are level.
+    fn check_node<T>(node: &Node<T>, b: usize, is_root: bool) -> (usize, usize) {
+        match node {
+            Node::Leaf(vals) => {
+                assert!(vals.len() <= b);
+                assert!(is_root || vals.len() >= b / 2);
+                (vals.len(), 0)
+            }
+            Node::Internal(children) => {
+                assert!(children.len() <= MAX_CHILDREN);
+                assert!(children.len() >= if is_root { 2 } else { MIN_CHILDREN });
+
+                let mut size = 0;
+                let depth = check_node(&children[0].1, b, false).1;
+                for (len, child) in children {
+                    assert_eq!(check_node(child, b, false), (*len, depth));
+                    size += len;
+                }
+                (size, depth + 1)
+            }
+        }
+    }
+
+    fn check<T, const B: usize>(tree: &ChunkedTreeList<T, B>) {
+        assert_eq!(check_node(&tree.root, B, true).0, tree.len());
+    }
+
+    impl_test_list!(ChunkedTreeList<usize, 2>, ChunkedTreeList::new(), check);
+    impl_test_list!(ChunkedTreeList<usize, 3>, ChunkedTreeList::new(), check);
+    impl_test_list!(ChunkedTreeList<usize, 64>, ChunkedTreeList::new(), check);
+
+    #[test]
+    fn test_conformance() {
+        conformance::run::<ChunkedTreeList<usize, 2>>();
+        conformance::run::<ChunkedTreeList<usize, 3>>();
+        conformance::run::<ChunkedTreeList<usize, 64>>();
+    }
+
+    #[test]
+    fn test_get_mut() {
+        let mut tree: ChunkedTreeList<usize, 4> = ChunkedTreeList::new();
+        for i in 0..100 {
+            tree.push_back(i);
+        }
+
+        for i in 0..100 {
+            *tree.get_mut(i).unwrap() *= 2;
+        }
+
+        assert_eq!(tree.get_mut(100), None);
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<usize>>(),
+            (0..100).map(|i| i * 2).collect::<Vec<usize>>()
+        );
+    }
+}
@@ -0,0 +1,274 @@
+use crate::balance::{Avl, BalancePolicy};
+use crate::tree_list::{Iter, TreeList};
+use std::iter::Chain;
+
+// Element `i` lives at `(origin + i) % len` in the underlying list, so rotating only moves the
+// origin. Indices passed in wrap around the length, so none of them is ever out of bounds
+// unless the list is empty.
+#[derive(Debug)]
+pub struct CircularTreeList<T, P: BalancePolicy = Avl> {
+    list: TreeList<T, P>,
+    origin: usize,
+}
+
+impl<T, P: BalancePolicy> Default for CircularTreeList<T, P> {
+    fn default() -> Self {
+        Self::with_policy()
+    }
+}
+
+impl<T> CircularTreeList<T> {
+    pub fn new() -> Self {
+        Self::with_policy()
+    }
+}
+
+impl<T, P: BalancePolicy> CircularTreeList<T, P> {
+    pub fn with_policy() -> Self {
+        CircularTreeList {
+            list: TreeList::with_policy(),
+            origin: 0,
+        }
+    }
+
+    // The position in the underlying list of the element at `index`, which must be in bounds.
+    fn physical(&self, index: usize) -> usize {
+        (self.origin + index) % self.list.len()
+    }
+
+    // Inserts `val` so that it ends up at `index`, for any `index` up to the length.
+    fn insert_aux(&mut self, index: usize, val: T) {
+        let len = self.list.len();
+        if self.origin + index < len {
+            self.list.insert(self.origin + index, val);
+        } else {
+            // Landing before the origin pushes the first element one place along.
+            self.list.insert(self.origin + index - len, val);
+            self.origin += 1;
+        }
+        // Appending to a list whose origin is at the front leaves it one past the end.
+        if self.origin == self.list.len() {
+            self.origin = 0;
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        if self.is_empty() {
+            None
+        } else {
+            self.list.get(self.physical(index % self.len()))
+        }
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if self.is_empty() {
+            None
+        } else {
+            let index = self.physical(index % self.len());
+            self.list.get_mut(index)
+        }
+    }
+
+    pub fn push_front(&mut self, val: T) {
+        self.insert_aux(0, val);
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.remove(0)
+    }
+
+    pub fn push_back(&mut self, val: T) {
+        self.insert_aux(self.len(), val);
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.remove(self.len().wrapping_sub(1))
+    }
+
+    /// Inserts `val` before the element at `index`, wrapping `index` around the length. Into an
+    /// empty list, `val` is inserted whatever the index.
+    pub fn insert(&mut self, index: usize, val: T) {
+        if self.is_empty() {
+            self.insert_aux(0, val);
+        } else {
+            self.insert_aux(index % self.len(), val);
+        }
+    }
+
+    /// Removes the element at `index`, wrapping `index` around the length. Returns `None` only if
+    /// the list is empty.
+    pub fn remove(&mut self, index: usize) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            let index = self.physical(index % self.len());
+            let res = self.list.remove(index);
+            if index < self.origin {
+                self.origin -= 1;
+            }
+            if self.origin == self.list.len() {
+                self.origin = 0;
+            }
+            Some(res)
+        }
+    }
+
+    /// Rotates the list `k` places to the left, so that the element at `k` becomes the first.
+    /// This only moves the origin, so it takes `O(1)` time.
+    pub fn rotate_left(&mut self, k: usize) {
+        if !self.is_empty() {
+            self.origin = self.physical(k % self.len());
+        }
+    }
+
+    /// Rotates the list `k` places to the right, so that the last `k` elements come first.
+    pub fn rotate_right(&mut self, k: usize) {
+        if !self.is_empty() {
+            let len = self.len();
+            self.origin = self.physical(len - k % len);
+        }
+    }
+
+    /// Unwraps the underlying `TreeList`, rotated so that the origin is at the front.
+    pub fn into_list(mut self) -> TreeList<T, P> {
+        self.list.rotate_left(self.origin);
+        self.list
+    }
+
+    pub fn clear(&mut self) {
+        self.list.clear();
+        self.origin = 0;
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn iter(&self) -> Chain<Iter<'_, T, P>, Iter<'_, T, P>> {
+        let tail = Iter::with_range(&self.list.root, self.origin, self.len());
+        let head = Iter::with_range(&self.list.root, 0, self.origin);
+        tail.chain(head)
+    }
+}
+
+impl<T, P: BalancePolicy> From<TreeList<T, P>> for CircularTreeList<T, P> {
+    fn from(list: TreeList<T, P>) -> Self {
+        CircularTreeList { list, origin: 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CircularTreeList;
+    use crate::balance::tests::Validate;
+    use crate::balance::{BalancePolicy, WeightBalanced};
+    use crate::tree_list::TreeList;
+
+    fn check<T, P: Validate + BalancePolicy>(tree: &CircularTreeList<T, P>) {
+        assert_eq!(P::validate(&tree.list.root), tree.len());
+        assert!(tree.origin < tree.len() || tree.origin == 0);
+    }
+
+    #[test]
+    fn test_empty_tree() {
+        let mut tree: CircularTreeList<char> = CircularTreeList::new();
+
+        tree.rotate_left(3);
+        tree.rotate_right(3);
+        assert_eq!(tree.len(), 0);
+        assert_eq!(tree.get(0), None);
+        assert_eq!(tree.remove(5), None);
+        assert_eq!(tree.pop_back(), None);
+        assert_eq!(tree.iter().copied().collect::<Vec<char>>(), [].to_vec());
+    }
+
+    #[test]
+    fn test_wrapping_indices() {
+        let mut tree: CircularTreeList<char> = CircularTreeList::new();
+        for c in "abcde".chars() {
+            tree.push_back(c);
+        }
+
+        tree.rotate_left(2);
+        assert_eq!(tree.iter().collect::<String>(), "cdeab");
+        assert_eq!(tree.get(0), Some(&'c'));
+        assert_eq!(tree.get(7), Some(&'e'));
+
+        tree.insert(4, 'x');
+        tree.push_back('y');
+        tree.push_front('z');
+        check(&tree);
+        assert_eq!(tree.iter().collect::<String>(), "zcdeaxby");
+
+        assert_eq!(tree.remove(9), Some('c'));
+        assert_eq!(tree.pop_back(), Some('y'));
+        tree.rotate_right(10);
+        check(&tree);
+        assert_eq!(tree.iter().collect::<String>(), "eaxbzd");
+        assert_eq!(tree.into_list().into_iter().collect::<String>(), "eaxbzd");
+    }
+
+    #[test]
+    fn test_push_back_keeps_origin_in_bounds() {
+        let mut tree: CircularTreeList<char> = CircularTreeList::new();
+        tree.push_back('a');
+        check(&tree);
+        tree.push_back('b');
+        tree.push_front('c');
+        check(&tree);
+        assert_eq!(tree.iter().collect::<String>(), "cab");
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let mut tree: CircularTreeList<usize, WeightBalanced> =
+            CircularTreeList::from(TreeList::from_vec((0..10).collect()));
+
+        let mut order = Vec::new();
+        for _ in 0..25 {
+            order.push(*tree.get(0).unwrap());
+            tree.rotate_left(1);
+        }
+        assert_eq!(order, (0..25).map(|i| i % 10).collect::<Vec<usize>>());
+    }
+
+    #[test]
+    fn test_random() {
+        let mut tree: CircularTreeList<usize> = CircularTreeList::new();
+        let mut vec: Vec<usize> = Vec::new();
+
+        for i in 0..1000 {
+            let index = (i * 7919) % (vec.len() + 1);
+            if i % 5 == 4 {
+                let index = index % vec.len();
+                assert_eq!(tree.remove(index + vec.len()), Some(vec.remove(index)));
+            } else if index == vec.len() {
+                tree.push_back(i);
+                vec.push(i);
+            } else {
+                tree.insert(index, i);
+                vec.insert(index, i);
+            }
+
+            let k = (i * 104_729) % (vec.len() + 1);
+            if i % 2 == 0 {
+                tree.rotate_left(k);
+                vec.rotate_left(k);
+            } else {
+                tree.rotate_right(k);
+                vec.rotate_right(k);
+            }
+            check(&tree);
+        }
+
+        assert_eq!(tree.iter().copied().collect::<Vec<usize>>(), vec);
+        for (i, val) in vec.iter().enumerate() {
+            *tree.get_mut(i).unwrap() += 1;
+            assert_eq!(tree.get(i), Some(&(val + 1)));
+        }
+    }
+}
@@ -0,0 +1,168 @@
+// Tests that every list should pass, whatever its layout. Each list opts in by implementing
+// `TestList` in its own test module, usually through `impl_test_list!`, and then runs `run`
+// alongside the tests that only make sense for it.
+
+pub(crate) trait TestList {
+    // An empty list. Lists with randomness of their own should be seeded, so that failures
+    // can be reproduced.
+    fn new() -> Self;
+
+    fn len(&self) -> usize;
+
+    // Takes `&mut self`, since some lists restructure themselves on every access.
+    fn get(&mut self, index: usize) -> Option<usize>;
+
+    fn insert(&mut self, index: usize, val: usize);
+
+    fn remove(&mut self, index: usize) -> usize;
+
+    fn push_front(&mut self, val: usize);
+
+    fn push_back(&mut self, val: usize);
+
+    fn pop_front(&mut self) -> Option<usize>;
+
+    fn pop_back(&mut self) -> Option<usize>;
+
+    fn to_vec(&mut self) -> Vec<usize>;
+
+    // Asserts whatever invariants the list keeps internally.
+    fn check(&self);
+}
+
+// Implements `TestList` for `$list` by calling the inherent methods of the same names, creating
+// lists with `$new` and checking them with `$check`. The methods are named through the type,
+// since method syntax could pick the trait's own `&mut self` versions instead.
+macro_rules! impl_test_list {
+    ($list:ty, $new:expr, $check:expr) => {
+        impl crate::conformance::TestList for $list {
+            fn new() -> Self {
+                $new
+            }
+
+            fn len(&self) -> usize {
+                <$list>::len(self)
+            }
+
+            fn get(&mut self, index: usize) -> Option<usize> {
+                <$list>::get(self, index).copied()
+            }
+
+            fn insert(&mut self, index: usize, val: usize) {
+                <$list>::insert(self, index, val)
+            }
+
+            fn remove(&mut self, index: usize) -> usize {
+                <$list>::remove(self, index)
+            }
+
+            fn push_front(&mut self, val: usize) {
+                <$list>::push_front(self, val)
+            }
+
+            fn push_back(&mut self, val: usize) {
+                <$list>::push_back(self, val)
+            }
+
+            fn pop_front(&mut self) -> Option<usize> {
+                <$list>::pop_front(self)
+            }
+
+            fn pop_back(&mut self) -> Option<usize> {
+                <$list>::pop_back(self)
+            }
+
+            fn to_vec(&mut self) -> Vec<usize> {
+                <$list>::iter(self).copied().collect()
+            }
+
+            fn check(&self) {
+                $check(self)
+            }
+        }
+    };
+}
+
+pub(crate) use impl_test_list;
+
+fn empty_list<L: TestList>() {
+    let mut list = L::new();
+
+    assert_eq!(list.len(), 0);
+    assert_eq!(list.get(0), None);
+    assert_eq!(list.pop_front(), None);
+    assert_eq!(list.pop_back(), None);
+    assert_eq!(list.to_vec(), [].to_vec());
+    list.check();
+}
+
+fn push_pop<L: TestList>() {
+    let mut list = L::new();
+
+    list.push_back(1);
+    list.push_back(2);
+    list.push_front(3);
+    list.push_front(4);
+
+    assert_eq!(list.pop_back(), Some(2));
+    assert_eq!(list.pop_front(), Some(4));
+    list.push_back(5);
+    list.push_front(6);
+
+    list.check();
+    assert_eq!(list.len(), 4);
+    assert_eq!(list.to_vec(), [6, 3, 1, 5].to_vec());
+}
+
+fn insert_remove<L: TestList>() {
+    let mut list = L::new();
+
+    list.insert(0, 1);
+    list.insert(0, 2);
+    list.insert(2, 3);
+    list.insert(1, 4);
+
+    assert_eq!(list.get(1), Some(4));
+    assert_eq!(list.remove(1), 4);
+    assert_eq!(list.remove(1), 1);
+
+    list.check();
+    assert_eq!(list.len(), 2);
+    assert_eq!(list.to_vec(), [2, 3].to_vec());
+}
+
+fn insert_remove_random<L: TestList>() {
+    let mut list = L::new();
+    let mut vec: Vec<usize> = Vec::new();
+
+    for i in 0..1000 {
+        let index = (i * 7919) % (vec.len() + 1);
+        list.insert(index, i);
+        vec.insert(index, i);
+        assert_eq!(list.get(index / 2), vec.get(index / 2).copied());
+    }
+    list.check();
+    assert_eq!(list.to_vec(), vec);
+
+    for i in 0..600 {
+        let index = (i * 104_729) % vec.len();
+        assert_eq!(list.remove(index), vec.remove(index));
+        if i % 50 == 0 {
+            list.check();
+        }
+    }
+
+    list.check();
+    assert_eq!(list.to_vec(), vec);
+    for (i, &val) in vec.iter().enumerate() {
+        assert_eq!(list.get(i), Some(val));
+    }
+    assert_eq!(list.get(vec.len()), None);
+}
+
+pub(crate) fn run<L: TestList>() {
+    empty_list::<L>();
+    push_pop::<L>();
+    insert_remove::<L>();
+    insert_remove_random::<L>();
+}
@@ -0,0 +1,705 @@
+use crate::augmented_tree_list::{AugmentedTreeList, Iter, Max, Min, Monoid, Sum, ValueMut};
+use crate::balance::{Avl, BalancePolicy};
+use crate::node::{Link, TreeNode};
+use crate::tree_list::resolve_range;
+use std::fmt;
+use std::marker::PhantomData;
+use std::ops::{Add, RangeBounds};
+
+/// An update that can be applied to a whole range of elements at once, such as adding the same
+/// amount to each of them.
+///
+/// Updates are left on the roots of subtrees until something needs to look further down, so
+/// updating a summary through `apply_summary` must agree with updating every element and
+/// summarizing them again.
+pub trait Action<T, M: Monoid<T>>: Clone {
+    fn apply(&self, val: &mut T);
+
+    /// The summary of `len` elements after the update, given their summary before it.
+    fn apply_summary(&self, summary: &M::Summary, len: usize) -> M::Summary;
+
+    /// A single update with the same effect as applying `self` and then `later`.
+    fn then(&self, later: &Self) -> Self;
+}
+
+/// Adds the same amount to every element.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AddAll<T>(pub T);
+
+/// Overwrites every element with the same value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SetAll<T>(pub T);
+
+// `val` added to itself `len` times, by doubling. Only sums that make up part of the result are
+// ever formed, so this overflows exactly when the result would.
+fn repeat<T: Copy + Default + Add<Output = T>>(mut val: T, mut len: usize) -> T {
+    let mut res = T::default();
+    while len > 0 {
+        if len % 2 == 1 {
+            res = res + val;
+        }
+        len /= 2;
+        if len > 0 {
+            val = val + val;
+        }
+    }
+    res
+}
+
+impl<T> Action<T, Sum> for AddAll<T>
+where
+    T: Copy + Default + Add<Output = T>,
+{
+    fn apply(&self, val: &mut T) {
+        *val = *val + self.0;
+    }
+
+    fn apply_summary(&self, summary: &T, len: usize) -> T {
+        *summary + repeat(self.0, len)
+    }
+
+    fn then(&self, later: &Self) -> Self {
+        AddAll(self.0 + later.0)
+    }
+}
+
+impl<T: Ord + Clone + Add<Output = T>> Action<T, Min> for AddAll<T> {
+    fn apply(&self, val: &mut T) {
+        *val = val.clone() + self.0.clone();
+    }
+
+    fn apply_summary(&self, summary: &Option<T>, _: usize) -> Option<T> {
+        summary.clone().map(|min| min + self.0.clone())
+    }
+
+    fn then(&self, later: &Self) -> Self {
+        AddAll(self.0.clone() + later.0.clone())
+    }
+}
+
+impl<T: Ord + Clone + Add<Output = T>> Action<T, Max> for AddAll<T> {
+    fn apply(&self, val: &mut T) {
+        *val = val.clone() + self.0.clone();
+    }
+
+    fn apply_summary(&self, summary: &Option<T>, _: usize) -> Option<T> {
+        summary.clone().map(|max| max + self.0.clone())
+    }
+
+    fn then(&self, later: &Self) -> Self {
+        AddAll(self.0.clone() + later.0.clone())
+    }
+}
+
+impl<T> Action<T, Sum> for SetAll<T>
+where
+    T: Copy + Default + Add<Output = T>,
+{
+    fn apply(&self, val: &mut T) {
+        *val = self.0;
+    }
+
+    fn apply_summary(&self, _: &T, len: usize) -> T {
+        repeat(self.0, len)
+    }
+
+    fn then(&self, later: &Self) -> Self {
+        *later
+    }
+}
+
+impl<T: Ord + Clone> Action<T, Min> for SetAll<T> {
+    fn apply(&self, val: &mut T) {
+        *val = self.0.clone();
+    }
+
+    fn apply_summary(&self, _: &Option<T>, len: usize) -> Option<T> {
+        if len == 0 {
+            None
+        } else {
+            Some(self.0.clone())
+        }
+    }
+
+    fn then(&self, later: &Self) -> Self {
+        later.clone()
+    }
+}
+
+impl<T: Ord + Clone> Action<T, Max> for SetAll<T> {
+    fn apply(&self, val: &mut T) {
+        *val = self.0.clone();
+    }
+
+    fn apply_summary(&self, _: &Option<T>, len: usize) -> Option<T> {
+        if len == 0 {
+            None
+        } else {
+            Some(self.0.clone())
+        }
+    }
+
+    fn then(&self, later: &Self) -> Self {
+        later.clone()
+    }
+}
+
+/// The monoid that `LazyTreeList` keeps its nodes under. Alongside the summary of `M`, each node
+/// records how many elements its subtree holds and the action still waiting to be passed on to
+/// its children.
+///
+/// Pending actions are only pushed down by operations that take `&mut self`, so the summaries
+/// below a node are stale until then. Read them through `LazyTreeList`, which accounts for that.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Lazy<M, A>(PhantomData<(M, A)>);
+
+#[derive(Debug, Clone)]
+pub struct LazySummary<S, A> {
+    summary: S,
+    len: usize,
+    action: Option<A>,
+}
+
+// Composes two optional actions, the first of which happened earlier.
+fn compose<T, M: Monoid<T>, A: Action<T, M>>(earlier: Option<&A>, later: Option<&A>) -> Option<A> {
+    match (earlier, later) {
+        (Some(earlier), Some(later)) => Some(earlier.then(later)),
+        (earlier, later) => earlier.or(later).cloned(),
+    }
+}
+
+// A node's own value and summary already include its pending action, which still has to be
+// passed on to both children.
+fn apply<T, M, A, P>(node: &mut TreeNode<T, P, Lazy<M, A>>, action: &A)
+where
+    M: Monoid<T>,
+    A: Action<T, M>,
+    P: BalancePolicy,
+{
+    action.apply(&mut node.val);
+    node.summary.summary = action.apply_summary(&node.summary.summary, node.summary.len);
+    node.summary.action = compose::<T, M, A>(node.summary.action.as_ref(), Some(action));
+}
+
+impl<T, M: Monoid<T>, A: Action<T, M>> Monoid<T> for Lazy<M, A> {
+    type Summary = LazySummary<M::Summary, A>;
+
+    fn identity() -> Self::Summary {
+        LazySummary {
+            summary: M::identity(),
+            len: 0,
+            action: None,
+        }
+    }
+
+    fn measure(val: &T) -> Self::Summary {
+        LazySummary {
+            summary: M::measure(val),
+            len: 1,
+            action: None,
+        }
+    }
+
+    // Only ever called once the node's own action has been pushed down.
+    fn combine(left: &Self::Summary, right: &Self::Summary) -> Self::Summary {
+        LazySummary {
+            summary: M::combine(&left.summary, &right.summary),
+            len: left.len + right.len,
+            action: None,
+        }
+    }
+
+    fn push_down<P: BalancePolicy>(node: &mut TreeNode<T, P, Self>) {
+        if let Some(action) = node.summary.action.take() {
+            for child in node.left.iter_mut().chain(node.right.iter_mut()) {
+                apply(child, &action);
+            }
+        }
+    }
+}
+
+// Applies `action` to the non-empty range `start..end` of the subtree `node`. Subtrees entirely
+// inside the range just take the action on their root, so at most two paths are walked.
+fn update_aux<T, M, A, P>(
+    node: &mut TreeNode<T, P, Lazy<M, A>>,
+    start: usize,
+    end: usize,
+    action: &A,
+) where
+    M: Monoid<T>,
+    A: Action<T, M>,
+    P: BalancePolicy,
+{
+    if start == 0 && end == node.summary.len {
+        apply(node, action);
+        return;
+    }
+
+    Lazy::push_down(node);
+    let num_to_left = node.num_to_left;
+    if start < num_to_left {
+        let end = std::cmp::min(end, num_to_left);
+        update_aux(node.left.as_mut().unwrap(), start, end, action);
+    }
+    if start <= num_to_left && num_to_left < end {
+        action.apply(&mut node.val);
+    }
+    if end > num_to_left + 1 {
+        let start = start.saturating_sub(num_to_left + 1);
+        let end = end - num_to_left - 1;
+        update_aux(node.right.as_mut().unwrap(), start, end, action);
+    }
+    node.update_summary();
+}
+
+// Folds the elements in `start..end` of the subtree `link`, where `pending` is everything still
+// waiting to be pushed down to it. Folding leaves the tree alone, so the pending actions are
+// applied to the summaries on the way out instead.
+fn fold_aux<T, M, A, P>(
+    link: &Link<T, P, Lazy<M, A>>,
+    start: usize,
+    end: usize,
+    pending: Option<&A>,
+) -> M::Summary
+where
+    M: Monoid<T>,
+    A: Action<T, M>,
+    P: BalancePolicy,
+{
+    let summary = match link {
+        None => M::identity(),
+        Some(_) if start >= end => M::identity(),
+        Some(node) if start == 0 && end == node.summary.len => node.summary.summary.clone(),
+        Some(node) => {
+            let num_to_left = node.num_to_left;
+            let action = node.summary.action.as_ref();
+            let mut res = M::identity();
+            if start < num_to_left {
+                let end = std::cmp::min(end, num_to_left);
+                res = fold_aux(&node.left, start, end, action);
+            }
+            if start <= num_to_left && num_to_left < end {
+                res = M::combine(&res, &M::measure(&node.val));
+            }
+            if end > num_to_left + 1 {
+                let start = start.saturating_sub(num_to_left + 1);
+                let end = end - num_to_left - 1;
+                let right = fold_aux(&node.right, start, end, action);
+                res = M::combine(&res, &right);
+            }
+            res
+        }
+    };
+
+    match pending {
+        Some(action) if start < end => action.apply_summary(&summary, end - start),
+        _ => summary,
+    }
+}
+
+// Collects copies of the elements of `link`, with `pending` and everything still waiting inside
+// the subtree applied to them, as `fold_aux` does for summaries.
+fn collect<T, M, A, P>(link: &Link<T, P, Lazy<M, A>>, pending: Option<&A>, vals: &mut Vec<T>)
+where
+    T: Clone,
+    M: Monoid<T>,
+    A: Action<T, M>,
+    P: BalancePolicy,
+{
+    if let Some(node) = link {
+        let below = compose::<T, M, A>(node.summary.action.as_ref(), pending);
+        collect(&node.left, below.as_ref(), vals);
+        let mut val = node.val.clone();
+        if let Some(action) = pending {
+            action.apply(&mut val);
+        }
+        vals.push(val);
+        collect(&node.right, below.as_ref(), vals);
+    }
+}
+
+// Pushes every pending action all the way down.
+fn flush<T, M, A, P>(link: &mut Link<T, P, Lazy<M, A>>)
+where
+    M: Monoid<T>,
+    A: Action<T, M>,
+    P: BalancePolicy,
+{
+    if let Some(node) = link {
+        Lazy::push_down(node);
+        flush(&mut node.left);
+        flush(&mut node.right);
+    }
+}
+
+// An `AugmentedTreeList` whose nodes can hold an action for their whole subtree, which its
+// operations push down on their way past.
+pub struct LazyTreeList<T, M: Monoid<T>, A: Action<T, M>, P: BalancePolicy = Avl> {
+    list: AugmentedTreeList<T, Lazy<M, A>, P>,
+}
+
+// Formatting can't push pending actions down, so it applies them to copies of the elements.
+impl<T, M, A, P> fmt::Debug for LazyTreeList<T, M, A, P>
+where
+    T: Clone + fmt::Debug,
+    M: Monoid<T>,
+    A: Action<T, M>,
+    P: BalancePolicy,
+{
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut vals = Vec::with_capacity(self.len());
+        collect(&self.list.root, None, &mut vals);
+        f.debug_list().entries(vals.iter()).finish()
+    }
+}
+
+impl<T, M: Monoid<T>, A: Action<T, M>> LazyTreeList<T, M, A> {
+    pub fn new() -> Self {
+        Self::with_policy()
+    }
+}
+
+impl<T, M: Monoid<T>, A: Action<T, M>, P: BalancePolicy> Default for LazyTreeList<T, M, A, P> {
+    fn default() -> Self {
+        Self::with_policy()
+    }
+}
+
+impl<T, M: Monoid<T>, A: Action<T, M>, P: BalancePolicy> LazyTreeList<T, M, A, P> {
+    pub fn with_policy() -> Self {
+        LazyTreeList {
+            list: AugmentedTreeList::with_policy(),
+        }
+    }
+
+    // Takes `&mut self`, since pending actions are pushed down on the way to the element.
+    pub fn get(&mut self, index: usize) -> Option<&T> {
+        if index >= self.len() {
+            None
+        } else {
+            Some(TreeNode::find_mut(self.list.root.as_mut().unwrap(), index))
+        }
+    }
+
+    // The guard clears the path to the element of pending actions up front, and refreshes the
+    // summaries along it when it is dropped.
+    pub fn get_mut(&mut self, index: usize) -> Option<ValueMut<'_, T, Lazy<M, A>, P>> {
+        self.list.get_mut(index)
+    }
+
+    pub fn push_front(&mut self, val: T) {
+        self.list.push_front(val);
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    pub fn push_back(&mut self, val: T) {
+        self.list.push_back(val);
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+
+    pub fn insert(&mut self, index: usize, val: T) {
+        self.list.insert(index, val);
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        self.list.remove(index)
+    }
+
+    /// Applies `action` to every element in `range` in `O(log n)` time, leaving it on the
+    /// subtrees that make up the range rather than visiting each element.
+    pub fn update_range<R: RangeBounds<usize>>(&mut self, range: R, action: A) {
+        let (start, end) = resolve_range(range, self.len());
+        if start < end {
+            update_aux(self.list.root.as_mut().unwrap(), start, end, &action);
+        }
+    }
+
+    /// Combines the summaries of the elements in `range`, in order.
+    pub fn fold<R: RangeBounds<usize>>(&self, range: R) -> M::Summary {
+        let (start, end) = resolve_range(range, self.len());
+        fold_aux(&self.list.root, start, end, None)
+    }
+
+    /// The summary of the whole list.
+    pub fn summary(&self) -> M::Summary {
+        self.list.summary().summary
+    }
+
+    pub fn clear(&mut self) {
+        self.list.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    // Visiting every element costs `O(n)` anyway, so this pushes everything down first and then
+    // iterates over the plain tree.
+    pub fn iter(&mut self) -> Iter<'_, T, P, Lazy<M, A>> {
+        flush(&mut self.list.root);
+        self.list.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{compose, Action, AddAll, Lazy, LazyTreeList, SetAll};
+    use crate::augmented_tree_list::{Max, Min, Monoid, Sum};
+    use crate::balance::tests::Validate;
+    use crate::balance::{Avl, RedBlack, Unbalanced, WeightBalanced};
+    use crate::conformance::{self, impl_test_list};
+    use crate::node::Link;
+
+    // Returns the size and real summary of the subtree at `link`, given the actions still
+    // waiting above it, asserting that every cached value is accurate.
+    fn check_node<T, M, A, P>(
+        link: &Link<T, P, Lazy<M, A>>,
+        pending: Option<&A>,
+    ) -> (usize, M::Summary)
+    where
+        T: Clone,
+        M: Monoid<T>,
+        A: Action<T, M>,
+        P: Validate,
+        M::Summary: PartialEq + std::fmt::Debug,
+    {
+        match link {
+            None => (0, M::identity()),
+            Some(node) => {
+                let below = compose::<T, M, A>(node.summary.action.as_ref(), pending);
+                let (left_size, left) = check_node::<T, M, A, P>(&node.left, below.as_ref());
+                let (right_size, right) = check_node::<T, M, A, P>(&node.right, below.as_ref());
+                let mut val = node.val.clone();
+                if let Some(action) = pending {
+                    action.apply(&mut val);
+                }
+                let summary = M::combine(&M::combine(&left, &M::measure(&val)), &right);
+                let size = left_size + right_size + 1;
+
+                assert_eq!(node.summary.len, size);
+                let cached = match pending {
+                    Some(action) => action.apply_summary(&node.summary.summary, size),
+                    None => node.summary.summary.clone(),
+                };
+                assert_eq!(cached, summary);
+                (size, summary)
+            }
+        }
+    }
+
+    fn check<T, M, A, P>(tree: &LazyTreeList<T, M, A, P>)
+    where
+        T: Clone,
+        M: Monoid<T>,
+        A: Action<T, M>,
+        P: Validate,
+        M::Summary: PartialEq + std::fmt::Debug,
+    {
+        assert_eq!(P::validate(&tree.list.root), tree.len());
+        assert_eq!(
+            check_node::<T, M, A, P>(&tree.list.root, None).0,
+            tree.len()
+        );
+    }
+
+    impl_test_list!(
+        LazyTreeList<usize, Sum, AddAll<usize>, Unbalanced>,
+        LazyTreeList::with_policy(),
+        check
+    );
+    impl_test_list!(LazyTreeList<usize, Sum, AddAll<usize>, Avl>, LazyTreeList::new(), check);
+    impl_test_list!(
+        LazyTreeList<usize, Sum, AddAll<usize>, WeightBalanced>,
+        LazyTreeList::with_policy(),
+        check
+    );
+    impl_test_list!(
+        LazyTreeList<usize, Sum, AddAll<usize>, RedBlack>,
+        LazyTreeList::with_policy(),
+        check
+    );
+
+    #[test]
+    fn test_conformance_policies() {
+        conformance::run::<LazyTreeList<usize, Sum, AddAll<usize>, Unbalanced>>();
+        conformance::run::<LazyTreeList<usize, Sum, AddAll<usize>, Avl>>();
+        conformance::run::<LazyTreeList<usize, Sum, AddAll<usize>, WeightBalanced>>();
+        conformance::run::<LazyTreeList<usize, Sum, AddAll<usize>, RedBlack>>();
+    }
+
+    #[test]
+    fn test_update_empty_tree() {
+        let mut tree: LazyTreeList<i64, Sum, AddAll<i64>> = LazyTreeList::new();
+
+        tree.update_range(.., AddAll(5));
+        assert!(tree.is_empty());
+        assert_eq!(tree.summary(), 0);
+        assert_eq!(tree.fold(..), 0);
+    }
+
+    #[test]
+    fn test_update_range() {
+        let mut tree: LazyTreeList<i64, Sum, AddAll<i64>> = LazyTreeList::new();
+        for i in 0..10 {
+            tree.push_back(i);
+        }
+
+        tree.update_range(2..5, AddAll(10));
+        tree.update_range(4.., AddAll(-1));
+        check(&tree);
+        assert_eq!(tree.fold(..), 45 + 30 - 6);
+        assert_eq!(tree.fold(3..=4), 13 + 13);
+        assert_eq!(tree.get(4), Some(&13));
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<i64>>(),
+            [0, 1, 12, 13, 13, 4, 5, 6, 7, 8].to_vec()
+        );
+    }
+
+    #[test]
+    fn test_debug_applies_pending_actions() {
+        let mut tree: LazyTreeList<i64, Sum, AddAll<i64>> = LazyTreeList::new();
+        for i in 0..8 {
+            tree.push_back(i);
+        }
+
+        tree.update_range(.., AddAll(100));
+        tree.update_range(2..5, AddAll(10));
+        assert_eq!(
+            format!("{:?}", tree),
+            "[100, 101, 112, 113, 114, 105, 106, 107]"
+        );
+        assert_eq!(
+            format!("{:?}", tree),
+            format!("{:?}", tree.iter().collect::<Vec<&i64>>())
+        );
+    }
+
+    #[test]
+    fn test_set_all_min() {
+        let mut tree: LazyTreeList<char, Min, SetAll<char>> = LazyTreeList::new();
+        for c in "lazytree".chars() {
+            tree.push_back(c);
+        }
+
+        tree.update_range(1..4, SetAll('x'));
+        tree.update_range(2..3, SetAll('b'));
+        check(&tree);
+        assert_eq!(tree.summary(), Some('b'));
+        assert_eq!(tree.fold(3..), Some('e'));
+        assert_eq!(tree.fold(1..2), Some('x'));
+        assert_eq!(tree.iter().collect::<String>(), "lxbxtree");
+    }
+
+    #[test]
+    fn test_get_mut_after_update() {
+        let mut tree: LazyTreeList<i64, Max, AddAll<i64>> = LazyTreeList::new();
+        for i in 0..100 {
+            tree.push_back(i);
+        }
+
+        tree.update_range(..50, AddAll(100));
+        *tree.get_mut(10).unwrap() += 1000;
+        {
+            let mut val = tree.get_mut(99).unwrap();
+            assert_eq!(*val, 99);
+            *val = -1;
+        }
+
+        check(&tree);
+        assert!(tree.get_mut(100).is_none());
+        assert_eq!(tree.get(10), Some(&1110));
+        assert_eq!(tree.summary(), Some(1110));
+        assert_eq!(tree.fold(50..), Some(98));
+    }
+
+    fn update_random<P: Validate>() {
+        let mut tree: LazyTreeList<i64, Sum, AddAll<i64>, P> = LazyTreeList::with_policy();
+        let mut mins: LazyTreeList<i64, Min, AddAll<i64>, P> = LazyTreeList::with_policy();
+        let mut vec: Vec<i64> = Vec::new();
+
+        for i in 0..500 {
+            let index = (i * 7919) % (vec.len() + 1);
+            let val = ((i * 104_729) % 1000) as i64;
+            tree.insert(index, val);
+            mins.insert(index, val);
+            vec.insert(index, val);
+
+            let start = (i * 31) % vec.len();
+            let end = start + (i * 17) % (vec.len() - start + 1);
+            let delta = (i % 21) as i64 - 10;
+            tree.update_range(start..end, AddAll(delta));
+            mins.update_range(start..end, AddAll(delta));
+            vec[start..end].iter_mut().for_each(|x| *x += delta);
+
+            if i % 4 == 0 {
+                let index = (i * 13) % vec.len();
+                let val = vec.remove(index);
+                assert_eq!(tree.remove(index), val);
+                assert_eq!(mins.remove(index), val);
+            }
+        }
+        check(&tree);
+        check(&mins);
+
+        for start in (0..vec.len()).step_by(7) {
+            for end in (start..=vec.len()).step_by(5) {
+                assert_eq!(tree.fold(start..end), vec[start..end].iter().sum::<i64>());
+                assert_eq!(mins.fold(start..end), vec[start..end].iter().min().copied());
+            }
+        }
+        assert_eq!(tree.iter().copied().collect::<Vec<i64>>(), vec);
+    }
+
+    #[test]
+    fn test_update_random_policies() {
+        update_random::<Unbalanced>();
+        update_random::<Avl>();
+        update_random::<WeightBalanced>();
+        update_random::<RedBlack>();
+    }
+
+    #[test]
+    fn test_sum_of_small_elements() {
+        let mut tree: LazyTreeList<u8, Sum, SetAll<u8>> = LazyTreeList::new();
+        for _ in 0..300 {
+            tree.push_back(0);
+        }
+
+        tree.update_range(.., SetAll(0));
+        tree.update_range(..255, SetAll(1));
+        check(&tree);
+        assert_eq!(tree.summary(), 255);
+        assert_eq!(tree.fold(100..), 155);
+    }
+
+    #[test]
+    fn test_sum_of_floats() {
+        let mut tree: LazyTreeList<f64, Sum, AddAll<f64>> = LazyTreeList::new();
+        for _ in 0..10 {
+            tree.push_back(0.5);
+        }
+
+        tree.update_range(2..8, AddAll(0.25));
+        assert_eq!(tree.summary(), 6.5);
+        assert_eq!(tree.fold(..4), 2.5);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_update_range_out_of_bounds() {
+        let mut tree: LazyTreeList<i64, Sum, SetAll<i64>> = LazyTreeList::new();
+        tree.push_back(1);
+        tree.update_range(0..2, SetAll(0));
+    }
+}
@@ -1,2 +1,19 @@
+pub mod arena_tree_list;
+pub mod augmented_tree_list;
+pub mod balance;
+pub mod chunked_tree_list;
+pub mod circular_tree_list;
+#[cfg(test)]
+mod conformance;
+pub mod lazy_tree_list;
+pub mod measured_tree_list;
+mod monoid;
+mod node;
+pub mod persistent_tree_list;
 pub mod recursive_tree_list;
+pub mod rope;
+pub mod sorted_tree_list;
+pub mod splay_tree_list;
+pub mod treap_list;
 pub mod tree_list;
+pub mod undo_tree_list;
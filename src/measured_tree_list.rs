@@ -0,0 +1,200 @@
+use crate::augmented_tree_list::{self, AugmentedTreeList, Monoid, ValueMut};
+use crate::balance::Avl;
+
+/// An element that takes up some amount of space, such as a row of a given height.
+pub trait Measured {
+    fn weight(&self) -> usize;
+}
+
+/// Sums the weights of a range of `Measured` elements.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Weight;
+
+impl<T: Measured> Monoid<T> for Weight {
+    type Summary = usize;
+
+    fn identity() -> usize {
+        0
+    }
+
+    fn measure(val: &T) -> usize {
+        val.weight()
+    }
+
+    fn combine(left: &usize, right: &usize) -> usize {
+        left + right
+    }
+}
+
+// Elements are laid end to end, so element `i` covers the offsets from the total weight of the
+// elements before it, up to but not including that plus its own weight.
+#[derive(Debug)]
+pub struct MeasuredTreeList<T: Measured> {
+    list: AugmentedTreeList<T, Weight>,
+}
+
+impl<T: Measured> Default for MeasuredTreeList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Measured> MeasuredTreeList<T> {
+    pub fn new() -> Self {
+        MeasuredTreeList {
+            list: AugmentedTreeList::new(),
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.list.get(index)
+    }
+
+    // Weights are refreshed when the returned guard is dropped.
+    pub fn get_mut(&mut self, index: usize) -> Option<ValueMut<'_, T, Weight>> {
+        self.list.get_mut(index)
+    }
+
+    pub fn push_front(&mut self, val: T) {
+        self.list.push_front(val);
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    pub fn push_back(&mut self, val: T) {
+        self.list.push_back(val);
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+
+    pub fn insert(&mut self, index: usize, val: T) {
+        self.list.insert(index, val);
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        self.list.remove(index)
+    }
+
+    pub fn total_weight(&self) -> usize {
+        self.list.summary()
+    }
+
+    /// The offset at which the element at `index` starts. An index equal to the length gives
+    /// the total weight.
+    pub fn offset_of(&self, index: usize) -> usize {
+        self.list.fold(..index)
+    }
+
+    /// The index of the element covering `offset`, or `None` if `offset` is past the end.
+    /// Elements with no weight cover no offsets, so they are never returned.
+    pub fn index_at_offset(&self, offset: usize) -> Option<usize> {
+        self.list.find_prefix(|&end| end > offset)
+    }
+
+    pub fn clear(&mut self) {
+        self.list.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn iter(&self) -> augmented_tree_list::Iter<'_, T, Avl, Weight> {
+        self.list.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Measured, MeasuredTreeList};
+
+    #[derive(Debug, PartialEq)]
+    struct Row(usize);
+
+    impl Measured for Row {
+        fn weight(&self) -> usize {
+            self.0
+        }
+    }
+
+    #[test]
+    fn test_empty_tree() {
+        let tree: MeasuredTreeList<Row> = MeasuredTreeList::new();
+
+        assert_eq!(tree.len(), 0);
+        assert_eq!(tree.total_weight(), 0);
+        assert_eq!(tree.offset_of(0), 0);
+        assert_eq!(tree.index_at_offset(0), None);
+    }
+
+    #[test]
+    fn test_offsets() {
+        let mut tree: MeasuredTreeList<Row> = MeasuredTreeList::new();
+        tree.push_back(Row(10));
+        tree.push_back(Row(0));
+        tree.push_back(Row(5));
+        tree.push_front(Row(20));
+
+        assert_eq!(tree.total_weight(), 35);
+        assert_eq!(
+            (0..=4).map(|i| tree.offset_of(i)).collect::<Vec<usize>>(),
+            [0, 20, 30, 30, 35].to_vec()
+        );
+        assert_eq!(tree.index_at_offset(0), Some(0));
+        assert_eq!(tree.index_at_offset(19), Some(0));
+        assert_eq!(tree.index_at_offset(20), Some(1));
+        assert_eq!(tree.index_at_offset(30), Some(3));
+        assert_eq!(tree.index_at_offset(34), Some(3));
+        assert_eq!(tree.index_at_offset(35), None);
+
+        assert_eq!(tree.remove(0), Row(20));
+        assert_eq!(tree.index_at_offset(10), Some(2));
+    }
+
+    #[test]
+    fn test_get_mut_updates_weight() {
+        let mut tree: MeasuredTreeList<Row> = MeasuredTreeList::new();
+        for i in 0..1000 {
+            tree.push_back(Row(i % 7));
+        }
+        let total = tree.total_weight();
+
+        tree.get_mut(500).unwrap().0 += 100;
+        assert_eq!(tree.total_weight(), total + 100);
+
+        let start = tree.offset_of(500);
+        let weight = tree.get(500).unwrap().0;
+        assert_eq!(tree.offset_of(501), start + weight);
+        assert_eq!(tree.index_at_offset(start + weight - 1), Some(500));
+    }
+
+    #[test]
+    fn test_offsets_random() {
+        let mut tree: MeasuredTreeList<Row> = MeasuredTreeList::new();
+        let mut vec: Vec<usize> = Vec::new();
+
+        for i in 0..300 {
+            let index = (i * 7919) % (vec.len() + 1);
+            tree.insert(index, Row(i % 13));
+            vec.insert(index, i % 13);
+        }
+
+        let mut offset = 0;
+        for (i, &weight) in vec.iter().enumerate() {
+            assert_eq!(tree.offset_of(i), offset);
+            for y in offset..offset + weight {
+                assert_eq!(tree.index_at_offset(y), Some(i));
+            }
+            offset += weight;
+        }
+        assert_eq!(tree.index_at_offset(offset), None);
+    }
+}
@@ -0,0 +1,32 @@
+use crate::balance::BalancePolicy;
+use crate::node::TreeNode;
+
+/// A way of summarizing runs of elements, such as their sum or minimum.
+///
+/// `combine` must be associative and `identity` must be neutral for it, but neither needs to be
+/// commutative: the summary of a range is always combined in list order.
+pub trait Monoid<T>: Sized {
+    type Summary: Clone;
+
+    fn identity() -> Self::Summary;
+
+    /// The summary of a range holding just `val`.
+    fn measure(val: &T) -> Self::Summary;
+
+    fn combine(left: &Self::Summary, right: &Self::Summary) -> Self::Summary;
+
+    /// Called on a node before anything below it is read or moved, for summaries that leave
+    /// work pending on the roots of subtrees, as `LazyTreeList` does. Others have nothing to do.
+    fn push_down<P: BalancePolicy>(_node: &mut TreeNode<T, P, Self>) {}
+}
+
+// Plain lists summarize nothing, so their nodes carry no summary to speak of.
+impl<T> Monoid<T> for () {
+    type Summary = ();
+
+    fn identity() {}
+
+    fn measure(_: &T) {}
+
+    fn combine(_: &(), _: &()) {}
+}
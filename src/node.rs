@@ -0,0 +1,543 @@
+use crate::balance::BalancePolicy;
+use crate::monoid::Monoid;
+use std::cmp::Ordering;
+use std::collections::VecDeque;
+use std::iter::FusedIterator;
+pub type Link<T, P, M = ()> = Option<Box<TreeNode<T, P, M>>>;
+
+// Where the element at `index` of a subtree lies relative to a root with `num_to_left`
+// elements on its left, together with its index within the child that holds it. This only
+// needs the count, so lists with node layouts of their own descend the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Position {
+    Left(usize),
+    Here,
+    Right(usize),
+}
+
+pub(crate) fn locate(index: usize, num_to_left: usize) -> Position {
+    match index.cmp(&num_to_left) {
+        Ordering::Less => Position::Left(index),
+        Ordering::Equal => Position::Here,
+        Ordering::Greater => Position::Right(index - num_to_left - 1),
+    }
+}
+
+// Every node caches the summary of its subtree under the monoid `M`. Plain lists use `()`,
+// which summarizes nothing, so the summary costs them neither space nor time.
+#[derive(Debug)]
+pub struct TreeNode<T, P: BalancePolicy, M: Monoid<T> = ()> {
+    pub(crate) val: T,
+    pub(crate) num_to_left: usize,
+    pub(crate) meta: P::Meta,
+    pub(crate) summary: M::Summary,
+    pub(crate) left: Link<T, P, M>,
+    pub(crate) right: Link<T, P, M>,
+}
+
+impl<T, P: BalancePolicy, M: Monoid<T>> TreeNode<T, P, M> {
+    pub(crate) fn new(val: T) -> Self {
+        TreeNode {
+            summary: M::measure(&val),
+            val,
+            num_to_left: 0,
+            meta: P::new_meta(),
+            left: None,
+            right: None,
+        }
+    }
+
+    pub(crate) fn summary_of(link: &Link<T, P, M>) -> M::Summary {
+        link.as_ref()
+            .map_or_else(M::identity, |node| node.summary.clone())
+    }
+
+    // Recomputes the summary from those of the children, which must not be waiting on anything
+    // still pending here. Policies call this whenever they repair a node.
+    pub(crate) fn update_summary(&mut self) {
+        let left = M::combine(&Self::summary_of(&self.left), &M::measure(&self.val));
+        self.summary = M::combine(&left, &Self::summary_of(&self.right));
+    }
+
+    // Rotations fix up `num_to_left` and the summaries; the caller is responsible for the
+    // balancing metadata.
+    pub(crate) fn rotate_left(mut node: Box<Self>) -> Box<Self> {
+        M::push_down(&mut node);
+        let mut right = node.right.take().unwrap();
+        M::push_down(&mut right);
+        node.right = right.left.take();
+        right.num_to_left += node.num_to_left + 1;
+        node.update_summary();
+        right.left = Some(node);
+        right.update_summary();
+        right
+    }
+
+    pub(crate) fn rotate_right(mut node: Box<Self>) -> Box<Self> {
+        M::push_down(&mut node);
+        let mut left = node.left.take().unwrap();
+        M::push_down(&mut left);
+        node.left = left.right.take();
+        node.num_to_left -= left.num_to_left + 1;
+        node.update_summary();
+        left.right = Some(node);
+        left.update_summary();
+        left
+    }
+
+    // Counts the elements for which `pred` holds, given that they all come before the ones for
+    // which it doesn't, by descending the tree once.
+    pub(crate) fn partition_point<F: FnMut(&T) -> bool>(
+        mut link: &Link<T, P, M>,
+        mut pred: F,
+    ) -> usize {
+        let mut index = 0;
+        while let Some(node) = link {
+            if pred(&node.val) {
+                index += node.num_to_left + 1;
+                link = &node.right;
+            } else {
+                link = &node.left;
+            }
+        }
+        index
+    }
+
+    // Like `slice::binary_search_by`, but stops at the first matching node on the way down,
+    // which may be any of several equal elements.
+    pub(crate) fn binary_search_by<F>(mut link: &Link<T, P, M>, mut f: F) -> Result<usize, usize>
+    where
+        F: FnMut(&T) -> Ordering,
+    {
+        let mut index = 0;
+        while let Some(node) = link {
+            match f(&node.val) {
+                Ordering::Less => {
+                    index += node.num_to_left + 1;
+                    link = &node.right;
+                }
+                Ordering::Greater => link = &node.left,
+                Ordering::Equal => return Ok(index + node.num_to_left),
+            }
+        }
+        Err(index)
+    }
+
+    // Finds the element at `index`, which must lie within the subtree.
+    pub(crate) fn find(mut node: &Self, mut index: usize) -> &T {
+        loop {
+            match locate(index, node.num_to_left) {
+                Position::Left(i) => {
+                    index = i;
+                    node = node.left.as_ref().unwrap();
+                }
+                Position::Right(i) => {
+                    index = i;
+                    node = node.right.as_ref().unwrap();
+                }
+                Position::Here => break &node.val,
+            }
+        }
+    }
+
+    // Like `find`, but pushes down anything pending on the way, so that every node on the path
+    // holds its real value.
+    pub(crate) fn find_mut(mut node: &mut Self, mut index: usize) -> &mut T {
+        loop {
+            M::push_down(node);
+            match locate(index, node.num_to_left) {
+                Position::Left(i) => {
+                    index = i;
+                    node = node.left.as_mut().unwrap();
+                }
+                Position::Right(i) => {
+                    index = i;
+                    node = node.right.as_mut().unwrap();
+                }
+                Position::Here => break &mut node.val,
+            }
+        }
+    }
+
+    // Frees a whole tree without recursing, however deep it is: whenever the current node has
+    // both subtrees it is rotated right, which shortens its left spine until it can be unlinked.
+    pub(crate) fn free(mut link: Link<T, P, M>) {
+        while let Some(mut node) = link {
+            match node.left.take() {
+                None => {
+                    link = node.right.take();
+                }
+                Some(mut left) => {
+                    if node.right.is_some() {
+                        node.left = left.right.take();
+                        left.right = Some(node);
+                    }
+                    link = Some(left);
+                }
+            }
+        }
+    }
+
+    // Makes `left` and `right` the subtrees of `mid`, where `left` holds `left_size` elements.
+    pub(crate) fn attach(
+        mut mid: Box<Self>,
+        left: Link<T, P, M>,
+        left_size: usize,
+        right: Link<T, P, M>,
+    ) -> Box<Self> {
+        mid.left = left;
+        mid.right = right;
+        mid.num_to_left = left_size;
+        mid.update_summary();
+        mid
+    }
+
+    // Joins `left`, `mid` and `right` by descending the right spine of `left` until reaching a
+    // subtree that `fits` alongside `right`, attaching the two there under `mid`, and
+    // rebalancing on the way back up.
+    pub(crate) fn join_right<F: Fn(&Self) -> bool>(
+        mut left: Box<Self>,
+        left_size: usize,
+        mid: Box<Self>,
+        right: Link<T, P, M>,
+        fits: &F,
+    ) -> Box<Self> {
+        M::push_down(&mut left);
+        let spine_size = left_size - left.num_to_left - 1;
+        left.right = Some(match left.right.take() {
+            Some(spine) if !fits(&spine) => Self::join_right(spine, spine_size, mid, right, fits),
+            spine => P::rebalance(Self::attach(mid, spine, spine_size, right)),
+        });
+        P::rebalance(left)
+    }
+
+    // The mirror image of `join_right`, descending the left spine of `right` instead.
+    pub(crate) fn join_left<F: Fn(&Self) -> bool>(
+        left: Link<T, P, M>,
+        left_size: usize,
+        mid: Box<Self>,
+        mut right: Box<Self>,
+        fits: &F,
+    ) -> Box<Self> {
+        M::push_down(&mut right);
+        right.num_to_left += left_size + 1;
+        right.left = Some(match right.left.take() {
+            Some(spine) if !fits(&spine) => Self::join_left(left, left_size, mid, spine, fits),
+            spine => P::rebalance(Self::attach(mid, left, left_size, spine)),
+        });
+        P::rebalance(right)
+    }
+}
+
+// Swapping values in place would leave the summaries above them stale, so only plain nodes can.
+impl<T, P: BalancePolicy> TreeNode<T, P> {
+    // Swaps the elements at `a < b` by descending to the node where their paths part, whose
+    // value and subtrees can then be borrowed separately.
+    pub(crate) fn swap(mut node: &mut Self, mut a: usize, mut b: usize) {
+        loop {
+            if b < node.num_to_left {
+                node = node.left.as_mut().unwrap();
+            } else if a > node.num_to_left {
+                a -= node.num_to_left + 1;
+                b -= node.num_to_left + 1;
+                node = node.right.as_mut().unwrap();
+            } else {
+                break;
+            }
+        }
+
+        let num_to_left = node.num_to_left;
+        let TreeNode {
+            val, left, right, ..
+        } = node;
+        let (first, second) = if a == num_to_left {
+            (
+                val,
+                Self::find_mut(right.as_mut().unwrap(), b - num_to_left - 1),
+            )
+        } else if b == num_to_left {
+            (Self::find_mut(left.as_mut().unwrap(), a), val)
+        } else {
+            let first = Self::find_mut(left.as_mut().unwrap(), a);
+            (
+                first,
+                Self::find_mut(right.as_mut().unwrap(), b - num_to_left - 1),
+            )
+        };
+        std::mem::swap(first, second);
+    }
+}
+
+// Walks the tree from both ends with a stack of pending ancestors at each end. The indices of
+// the next element at either end are tracked too, so the iterator knows its exact length and can
+// skip ahead by seeking from the root rather than stepping through every element in between.
+pub struct Iter<'a, T, P: BalancePolicy, M: Monoid<T> = ()> {
+    root: &'a Link<T, P, M>,
+    front: Vec<&'a TreeNode<T, P, M>>,
+    back: Vec<&'a TreeNode<T, P, M>>,
+    start: usize,
+    end: usize,
+}
+
+impl<'a, T, P: BalancePolicy, M: Monoid<T>> Iter<'a, T, P, M> {
+    pub(crate) fn new(root: &'a Link<T, P, M>, len: usize) -> Self {
+        Self::with_range(root, 0, len)
+    }
+
+    // An iterator over only the elements in `start..end`.
+    pub(crate) fn with_range(root: &'a Link<T, P, M>, start: usize, end: usize) -> Self {
+        let mut iter = Iter {
+            root,
+            front: Vec::new(),
+            back: Vec::new(),
+            start,
+            end,
+        };
+
+        if start < end {
+            iter.seek_front(start);
+            iter.seek_back(end - 1);
+        }
+        iter
+    }
+
+    // Rebuilds the front stack so that the element at `index` is on top.
+    fn seek_front(&mut self, mut index: usize) {
+        self.front.clear();
+        let mut curr = self.root;
+        while let Some(node) = curr {
+            match index.cmp(&node.num_to_left) {
+                Ordering::Less => {
+                    self.front.push(node);
+                    curr = &node.left;
+                }
+                Ordering::Greater => {
+                    index -= node.num_to_left + 1;
+                    curr = &node.right;
+                }
+                Ordering::Equal => {
+                    self.front.push(node);
+                    break;
+                }
+            }
+        }
+    }
+
+    fn seek_back(&mut self, mut index: usize) {
+        self.back.clear();
+        let mut curr = self.root;
+        while let Some(node) = curr {
+            match index.cmp(&node.num_to_left) {
+                Ordering::Less => curr = &node.left,
+                Ordering::Greater => {
+                    index -= node.num_to_left + 1;
+                    self.back.push(node);
+                    curr = &node.right;
+                }
+                Ordering::Equal => {
+                    self.back.push(node);
+                    break;
+                }
+            }
+        }
+    }
+}
+
+impl<'a, T, P: BalancePolicy, M: Monoid<T>> Iterator for Iter<'a, T, P, M> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            return None;
+        }
+
+        let node = self.front.pop().unwrap();
+        let mut curr = &node.right;
+        while let Some(ref x) = curr {
+            self.front.push(x);
+            curr = &x.left;
+        }
+        self.start += 1;
+        Some(&node.val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.end - self.start;
+        (len, Some(len))
+    }
+
+    fn count(self) -> usize {
+        self.len()
+    }
+
+    fn last(mut self) -> Option<Self::Item> {
+        self.next_back()
+    }
+
+    fn nth(&mut self, n: usize) -> Option<Self::Item> {
+        if n >= self.len() {
+            self.start = self.end;
+            None
+        } else {
+            self.start += n;
+            self.seek_front(self.start);
+            self.next()
+        }
+    }
+}
+
+impl<'a, T, P: BalancePolicy, M: Monoid<T>> DoubleEndedIterator for Iter<'a, T, P, M> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.start == self.end {
+            return None;
+        }
+
+        let node = self.back.pop().unwrap();
+        let mut curr = &node.left;
+        while let Some(ref x) = curr {
+            self.back.push(x);
+            curr = &x.right;
+        }
+        self.end -= 1;
+        Some(&node.val)
+    }
+
+    fn nth_back(&mut self, n: usize) -> Option<Self::Item> {
+        if n >= self.len() {
+            self.end = self.start;
+            None
+        } else {
+            self.end -= n;
+            self.seek_back(self.end - 1);
+            self.next_back()
+        }
+    }
+}
+
+impl<'a, T, P: BalancePolicy, M: Monoid<T>> ExactSizeIterator for Iter<'a, T, P, M> {}
+
+impl<'a, T, P: BalancePolicy, M: Monoid<T>> FusedIterator for Iter<'a, T, P, M> {}
+
+impl<'a, T, P: BalancePolicy, M: Monoid<T>> Clone for Iter<'a, T, P, M> {
+    fn clone(&self) -> Self {
+        Iter {
+            root: self.root,
+            front: self.front.clone(),
+            back: self.back.clone(),
+            start: self.start,
+            end: self.end,
+        }
+    }
+}
+
+// A mutable borrow of a whole node can't coexist with borrows of its children, so each stack
+// entry holds the disjoint borrows of a pending node's value and right subtree instead.
+pub struct IterMut<'a, T, P: BalancePolicy> {
+    stack: Vec<(&'a mut T, &'a mut Link<T, P>)>,
+    len: usize,
+}
+
+impl<'a, T, P: BalancePolicy> IterMut<'a, T, P> {
+    pub(crate) fn new(root: &'a mut Link<T, P>, len: usize) -> Self {
+        let mut iter = IterMut {
+            stack: Vec::new(),
+            len,
+        };
+        iter.push_left(root);
+        iter
+    }
+
+    fn push_left(&mut self, mut curr: &'a mut Link<T, P>) {
+        while let Some(node) = curr {
+            let TreeNode {
+                val, left, right, ..
+            } = &mut **node;
+            self.stack.push((val, right));
+            curr = left;
+        }
+    }
+}
+
+impl<'a, T, P: BalancePolicy> Iterator for IterMut<'a, T, P> {
+    type Item = &'a mut T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (val, right) = self.stack.pop()?;
+        self.push_left(right);
+        self.len -= 1;
+        Some(val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<'a, T, P: BalancePolicy> ExactSizeIterator for IterMut<'a, T, P> {}
+
+impl<'a, T, P: BalancePolicy> FusedIterator for IterMut<'a, T, P> {}
+
+// Yields the elements of a detached tree by value from either end. The deque holds subtrees in
+// order; the ends are split open as needed, so each node is moved only a constant number of
+// times and the tree is never walked recursively, not even when dropped.
+pub struct IntoIter<T, P: BalancePolicy> {
+    nodes: VecDeque<Box<TreeNode<T, P>>>,
+    len: usize,
+}
+
+impl<T, P: BalancePolicy> IntoIter<T, P> {
+    pub(crate) fn new(root: Link<T, P>, len: usize) -> Self {
+        IntoIter {
+            nodes: root.into_iter().collect(),
+            len,
+        }
+    }
+}
+
+impl<T, P: BalancePolicy> Iterator for IntoIter<T, P> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut node = self.nodes.pop_front()?;
+        while let Some(left) = node.left.take() {
+            self.nodes.push_front(node);
+            node = left;
+        }
+
+        if let Some(right) = node.right.take() {
+            self.nodes.push_front(right);
+        }
+        self.len -= 1;
+        Some(node.val)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.len, Some(self.len))
+    }
+}
+
+impl<T, P: BalancePolicy> DoubleEndedIterator for IntoIter<T, P> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        let mut node = self.nodes.pop_back()?;
+        while let Some(right) = node.right.take() {
+            self.nodes.push_back(node);
+            node = right;
+        }
+
+        if let Some(left) = node.left.take() {
+            self.nodes.push_back(left);
+        }
+        self.len -= 1;
+        Some(node.val)
+    }
+}
+
+impl<T, P: BalancePolicy> ExactSizeIterator for IntoIter<T, P> {}
+
+impl<T, P: BalancePolicy> FusedIterator for IntoIter<T, P> {}
+
+impl<T, P: BalancePolicy> Drop for IntoIter<T, P> {
+    fn drop(&mut self) {
+        for _ in self {}
+    }
+}
@@ -0,0 +1,442 @@
+use crate::balance::{Avl, Rotation};
+use crate::node::{locate, Position};
+use std::rc::Rc;
+type Link<T> = Option<Rc<TreeNode<T>>>;
+
+// Nodes are never modified once built. An edit copies the nodes on the path it walks down and
+// points the copies at the untouched subtrees, which every version shares. Values sit behind an
+// `Rc` of their own, so a copy shares its value too and `T` is never cloned.
+#[derive(Debug)]
+struct TreeNode<T> {
+    val: Rc<T>,
+    num_to_left: usize,
+    height: usize,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+// Every edit returns a new version and leaves `self` as it was. Cloning a version only bumps
+// the count on its root, so it takes `O(1)` time.
+#[derive(Debug)]
+pub struct PersistentTreeList<T> {
+    root: Link<T>,
+    size: usize,
+}
+
+impl<T> Clone for PersistentTreeList<T> {
+    fn clone(&self) -> Self {
+        PersistentTreeList {
+            root: self.root.clone(),
+            size: self.size,
+        }
+    }
+}
+
+impl<T> Default for PersistentTreeList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn height<T>(link: &Link<T>) -> usize {
+    link.as_ref().map_or(0, |node| node.height)
+}
+
+fn children<T>(link: &Link<T>) -> (&Link<T>, &Link<T>) {
+    let node = link.as_ref().unwrap();
+    (&node.left, &node.right)
+}
+
+fn node<T>(val: Rc<T>, num_to_left: usize, left: Link<T>, right: Link<T>) -> Rc<TreeNode<T>> {
+    Rc::new(TreeNode {
+        val,
+        num_to_left,
+        height: Avl::parent_height(height(&left), height(&right)),
+        left,
+        right,
+    })
+}
+
+// Builds a node from the given parts, rotating as `Avl` would if the two sides differ in height
+// by two. The rotations can't happen in place, so the nodes they move are copied too.
+fn balance<T>(val: Rc<T>, num_to_left: usize, left: Link<T>, right: Link<T>) -> Rc<TreeNode<T>> {
+    match Avl::rotation(&left, &right, height, children) {
+        Rotation::None => node(val, num_to_left, left, right),
+        Rotation::Right => {
+            let l = left.unwrap();
+            let num_to_right = num_to_left - l.num_to_left - 1;
+            let right = node(val, num_to_right, l.right.clone(), right);
+            node(l.val.clone(), l.num_to_left, l.left.clone(), Some(right))
+        }
+        Rotation::LeftRight => {
+            let l = left.unwrap();
+            let lr = l.right.as_ref().unwrap();
+            let num_to_right = num_to_left - l.num_to_left - lr.num_to_left - 2;
+            let left = node(
+                l.val.clone(),
+                l.num_to_left,
+                l.left.clone(),
+                lr.left.clone(),
+            );
+            let right = node(val, num_to_right, lr.right.clone(), right);
+            let num_to_left = l.num_to_left + lr.num_to_left + 1;
+            node(lr.val.clone(), num_to_left, Some(left), Some(right))
+        }
+        Rotation::Left => {
+            let r = right.unwrap();
+            let left = node(val, num_to_left, left, r.left.clone());
+            let num_to_left = num_to_left + r.num_to_left + 1;
+            node(r.val.clone(), num_to_left, Some(left), r.right.clone())
+        }
+        Rotation::RightLeft => {
+            let r = right.unwrap();
+            let rl = r.left.as_ref().unwrap();
+            let num_to_right = r.num_to_left - rl.num_to_left - 1;
+            let left = node(val, num_to_left, left, rl.left.clone());
+            let right = node(
+                r.val.clone(),
+                num_to_right,
+                rl.right.clone(),
+                r.right.clone(),
+            );
+            let num_to_left = num_to_left + rl.num_to_left + 1;
+            node(rl.val.clone(), num_to_left, Some(left), Some(right))
+        }
+    }
+}
+
+fn insert_aux<T>(link: &Link<T>, index: usize, val: Rc<T>) -> Rc<TreeNode<T>> {
+    match link {
+        None => node(val, 0, None, None),
+        Some(x) => {
+            if index <= x.num_to_left {
+                let left = insert_aux(&x.left, index, val);
+                balance(
+                    x.val.clone(),
+                    x.num_to_left + 1,
+                    Some(left),
+                    x.right.clone(),
+                )
+            } else {
+                let right = insert_aux(&x.right, index - x.num_to_left - 1, val);
+                balance(x.val.clone(), x.num_to_left, x.left.clone(), Some(right))
+            }
+        }
+    }
+}
+
+fn remove_aux<T>(x: &Rc<TreeNode<T>>, index: usize) -> (Link<T>, Rc<T>) {
+    match locate(index, x.num_to_left) {
+        Position::Left(index) => {
+            let (left, res) = remove_aux(x.left.as_ref().unwrap(), index);
+            let root = balance(x.val.clone(), x.num_to_left - 1, left, x.right.clone());
+            (Some(root), res)
+        }
+        Position::Right(index) => {
+            let (right, res) = remove_aux(x.right.as_ref().unwrap(), index);
+            let root = balance(x.val.clone(), x.num_to_left, x.left.clone(), right);
+            (Some(root), res)
+        }
+        Position::Here => match (&x.left, &x.right) {
+            (left, None) => (left.clone(), x.val.clone()),
+            (None, right) => (right.clone(), x.val.clone()),
+            (left, Some(right)) => {
+                let (right, succ) = remove_aux(right, 0);
+                let root = balance(succ, x.num_to_left, left.clone(), right);
+                (Some(root), x.val.clone())
+            }
+        },
+    }
+}
+
+// Copies the path to `index` without changing the shape of the tree.
+fn set_aux<T>(x: &Rc<TreeNode<T>>, index: usize, val: Rc<T>) -> Rc<TreeNode<T>> {
+    let (val, left, right) = match locate(index, x.num_to_left) {
+        Position::Left(index) => {
+            let left = set_aux(x.left.as_ref().unwrap(), index, val);
+            (x.val.clone(), Some(left), x.right.clone())
+        }
+        Position::Right(index) => {
+            let right = set_aux(x.right.as_ref().unwrap(), index, val);
+            (x.val.clone(), x.left.clone(), Some(right))
+        }
+        Position::Here => (val, x.left.clone(), x.right.clone()),
+    };
+    Rc::new(TreeNode {
+        val,
+        num_to_left: x.num_to_left,
+        height: x.height,
+        left,
+        right,
+    })
+}
+
+impl<T> PersistentTreeList<T> {
+    pub fn new() -> Self {
+        PersistentTreeList {
+            root: None,
+            size: 0,
+        }
+    }
+
+    pub fn get(&self, mut index: usize) -> Option<&T> {
+        if index >= self.size {
+            None
+        } else {
+            let mut node = self.root.as_ref().unwrap();
+
+            loop {
+                match locate(index, node.num_to_left) {
+                    Position::Left(i) => {
+                        index = i;
+                        node = node.left.as_ref().unwrap();
+                    }
+                    Position::Right(i) => {
+                        index = i;
+                        node = node.right.as_ref().unwrap();
+                    }
+                    Position::Here => break Some(&*node.val),
+                }
+            }
+        }
+    }
+
+    /// Whether two versions share the same tree, in which case they certainly hold the same
+    /// elements.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        match (&self.root, &other.root) {
+            (Some(a), Some(b)) => Rc::ptr_eq(a, b),
+            (a, b) => a.is_none() && b.is_none(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut curr = &self.root;
+        let mut stack: Vec<&TreeNode<T>> = Vec::new();
+        while let Some(ref node) = curr {
+            stack.push(node);
+            curr = &node.left;
+        }
+
+        Iter { stack }
+    }
+
+    pub fn push_front(&self, val: T) -> Self {
+        self.insert(0, val)
+    }
+
+    pub fn push_back(&self, val: T) -> Self {
+        self.insert(self.size, val)
+    }
+
+    pub fn insert(&self, index: usize, val: T) -> Self {
+        if index > self.size {
+            panic!("Index out of bounds!");
+        } else {
+            PersistentTreeList {
+                root: Some(insert_aux(&self.root, index, Rc::new(val))),
+                size: self.size + 1,
+            }
+        }
+    }
+
+    pub fn set(&self, index: usize, val: T) -> Self {
+        if index >= self.size {
+            panic!("Index out of bounds!");
+        } else {
+            PersistentTreeList {
+                root: Some(set_aux(self.root.as_ref().unwrap(), index, Rc::new(val))),
+                size: self.size,
+            }
+        }
+    }
+}
+
+impl<T: Clone> PersistentTreeList<T> {
+    /// Returns the new version along with a clone of the removed element, which earlier versions
+    /// may still hold.
+    pub fn remove(&self, index: usize) -> (Self, T) {
+        if index >= self.size {
+            panic!("Index out of bounds!");
+        } else {
+            let (root, res) = remove_aux(self.root.as_ref().unwrap(), index);
+            let list = PersistentTreeList {
+                root,
+                size: self.size - 1,
+            };
+            (list, T::clone(&res))
+        }
+    }
+}
+
+pub struct Iter<'a, T> {
+    stack: Vec<&'a TreeNode<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.stack.pop();
+
+        next.map(|node| {
+            let mut curr = &node.right;
+            while let Some(ref x) = curr {
+                self.stack.push(x);
+                curr = &x.left;
+            }
+            &*node.val
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Link, PersistentTreeList};
+    use std::rc::Rc;
+
+    // Returns the size and height of the subtree at `link`, asserting that every node's cached
+    // values are accurate and that the tree is AVL-balanced.
+    fn check_node<T>(link: &Link<T>) -> (usize, usize) {
+        match link {
+            None => (0, 0),
+            Some(node) => {
+                let (left_size, left_height) = check_node(&node.left);
+                let (right_size, right_height) = check_node(&node.right);
+                let height = 1 + std::cmp::max(left_height, right_height);
+
+                assert_eq!(node.num_to_left, left_size);
+                assert!(left_height <= right_height + 1 && right_height <= left_height + 1);
+                assert_eq!(node.height, height);
+                (left_size + right_size + 1, height)
+            }
+        }
+    }
+
+    fn check<T>(tree: &PersistentTreeList<T>) {
+        assert_eq!(check_node(&tree.root).0, tree.len());
+    }
+
+    #[test]
+    fn test_empty_tree() {
+        let tree: PersistentTreeList<char> = PersistentTreeList::new();
+
+        assert_eq!(tree.len(), 0);
+        assert_eq!(tree.get(0), None);
+        assert!(tree.ptr_eq(&tree.clone()));
+        assert_eq!(tree.iter().copied().collect::<Vec<char>>(), [].to_vec());
+    }
+
+    #[test]
+    fn test_versions() {
+        let empty: PersistentTreeList<char> = PersistentTreeList::new();
+        let a = empty.push_back('a');
+        let ab = a.push_back('b');
+        let cab = ab.push_front('c');
+        let (cb, removed) = cab.remove(1);
+        let xb = cb.set(0, 'x');
+
+        assert_eq!(removed, 'a');
+        for (version, expected) in [
+            (&empty, ""),
+            (&a, "a"),
+            (&ab, "ab"),
+            (&cab, "cab"),
+            (&cb, "cb"),
+            (&xb, "xb"),
+        ]
+        .iter()
+        {
+            check(version);
+            assert_eq!(version.iter().collect::<String>(), *expected);
+        }
+    }
+
+    #[test]
+    fn test_structural_sharing() {
+        let mut tree: PersistentTreeList<usize> = PersistentTreeList::new();
+        for i in 0..1000 {
+            tree = tree.push_back(i);
+        }
+
+        let copy = tree.clone();
+        assert!(copy.ptr_eq(&tree));
+
+        let edited = tree.set(0, 1000);
+        assert!(!edited.ptr_eq(&tree));
+        let (old, new) = (tree.root.as_ref().unwrap(), edited.root.as_ref().unwrap());
+        assert!(Rc::ptr_eq(
+            old.right.as_ref().unwrap(),
+            new.right.as_ref().unwrap()
+        ));
+        assert_eq!(tree.get(0), Some(&0));
+        assert_eq!(edited.get(0), Some(&1000));
+        for i in 1..1000 {
+            assert!(std::ptr::eq(tree.get(i).unwrap(), edited.get(i).unwrap()));
+        }
+    }
+
+    #[test]
+    fn test_values_without_clone() {
+        #[derive(Debug, PartialEq)]
+        struct Token(usize);
+
+        let mut tree: PersistentTreeList<Token> = PersistentTreeList::new();
+        for i in 0..100 {
+            tree = tree.insert(i / 2, Token(i));
+        }
+        let edited = tree.set(50, Token(100));
+
+        check(&edited);
+        assert_eq!(tree.get(50), Some(&Token(98)));
+        assert_eq!(edited.get(50), Some(&Token(100)));
+        assert_eq!(edited.iter().count(), 100);
+    }
+
+    #[test]
+    fn test_random_versions() {
+        let mut versions: Vec<PersistentTreeList<usize>> = vec![PersistentTreeList::new()];
+        let mut vecs: Vec<Vec<usize>> = vec![Vec::new()];
+
+        for i in 0..600 {
+            let base = (i * 31) % versions.len();
+            let mut vec = vecs[base].clone();
+            let tree = if i % 4 == 3 && !vec.is_empty() {
+                let index = (i * 7919) % vec.len();
+                let (tree, val) = versions[base].remove(index);
+                assert_eq!(val, vec.remove(index));
+                tree
+            } else if i % 4 == 2 && !vec.is_empty() {
+                let index = (i * 7919) % vec.len();
+                vec[index] = i;
+                versions[base].set(index, i)
+            } else {
+                let index = (i * 104_729) % (vec.len() + 1);
+                vec.insert(index, i);
+                versions[base].insert(index, i)
+            };
+            versions.push(tree);
+            vecs.push(vec);
+        }
+
+        for (tree, vec) in versions.iter().zip(vecs.iter()) {
+            check(tree);
+            assert_eq!(&tree.iter().copied().collect::<Vec<usize>>(), vec);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of bounds!")]
+    fn test_set_out_of_bounds() {
+        let tree: PersistentTreeList<usize> = PersistentTreeList::new();
+        tree.push_back(0).set(1, 1);
+    }
+}
@@ -1,41 +1,53 @@
+use crate::balance::{BalancePolicy, RedBlack};
+pub use crate::node::{IntoIter, Iter, IterMut};
+use crate::node::{Link, TreeNode};
+pub use crate::tree_list::TreeListError;
 use std::cmp::Ordering;
-type Link<T> = Option<Box<TreeNode<T>>>;
-
-#[derive(Debug)]
-struct TreeNode<T> {
-    val: T,
-    num_to_left: usize,
-    left: Link<T>,
-    right: Link<T>,
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::FromIterator;
+use std::ops::{Index, IndexMut};
+
+pub struct RecursiveTreeList<T, P: BalancePolicy = RedBlack> {
+    root: Link<T, P>,
+    size: usize,
 }
 
-impl<T> TreeNode<T> {
-    fn new(val: T) -> Self {
-        TreeNode {
-            val,
-            num_to_left: 0,
-            left: None,
-            right: None,
-        }
+impl<T> RecursiveTreeList<T> {
+    pub fn new() -> Self {
+        Self::with_policy()
     }
 }
 
-#[derive(Debug, Default)]
-pub struct RecursiveTreeList<T> {
-    root: Link<T>,
-    size: usize,
+impl<T, P: BalancePolicy> Default for RecursiveTreeList<T, P> {
+    fn default() -> Self {
+        Self::with_policy()
+    }
 }
 
-impl<T> RecursiveTreeList<T> {
-    pub fn new() -> Self {
+impl<T, P: BalancePolicy> RecursiveTreeList<T, P> {
+    pub fn with_policy() -> Self {
         RecursiveTreeList {
             root: None,
             size: 0,
         }
     }
 
+    // Builds a balanced tree directly, in linear time, rather than inserting one by one.
+    pub fn from_vec(vec: Vec<T>) -> Self {
+        Self::from_exact_iter(vec.into_iter())
+    }
+
+    fn from_exact_iter<I: ExactSizeIterator<Item = T>>(mut iter: I) -> Self {
+        let size = iter.len();
+        RecursiveTreeList {
+            root: P::build(&mut iter, size),
+            size,
+        }
+    }
+
     pub fn get(&self, mut index: usize) -> Option<&T> {
-        if index > self.size {
+        if index >= self.size {
             None
         } else {
             let mut node = self.root.as_ref().unwrap();
@@ -54,7 +66,7 @@ impl<T> RecursiveTreeList<T> {
     }
 
     pub fn get_mut(&mut self, mut index: usize) -> Option<&mut T> {
-        if index > self.size {
+        if index >= self.size {
             None
         } else {
             let mut node = self.root.as_mut().unwrap();
@@ -72,31 +84,57 @@ impl<T> RecursiveTreeList<T> {
         }
     }
 
-    fn push_front_aux(node: Link<T>, val: T) -> Link<T> {
+    // These assume the list is sorted, as the slice methods of the same names do. Each one
+    // descends the tree once, so it costs no more than a single `get`.
+    pub fn binary_search(&self, x: &T) -> Result<usize, usize>
+    where
+        T: Ord,
+    {
+        self.binary_search_by(|val| val.cmp(x))
+    }
+
+    pub fn binary_search_by<F: FnMut(&T) -> Ordering>(&self, f: F) -> Result<usize, usize> {
+        TreeNode::binary_search_by(&self.root, f)
+    }
+
+    pub fn binary_search_by_key<B, F>(&self, b: &B, mut f: F) -> Result<usize, usize>
+    where
+        B: Ord,
+        F: FnMut(&T) -> B,
+    {
+        self.binary_search_by(|val| f(val).cmp(b))
+    }
+
+    pub fn partition_point<F: FnMut(&T) -> bool>(&self, pred: F) -> usize {
+        TreeNode::partition_point(&self.root, pred)
+    }
+
+    fn push_front_aux(node: Link<T, P>, val: T) -> Box<TreeNode<T, P>> {
         match node {
-            None => Some(Box::new(TreeNode::new(val))),
+            None => Box::new(TreeNode::new(val)),
             Some(x) => {
                 let mut x = x;
                 x.num_to_left += 1;
-                x.left = Self::push_front_aux(x.left, val);
-                Some(x)
+                x.left = Some(Self::push_front_aux(x.left, val));
+                P::rebalance(x)
             }
         }
     }
 
     pub fn push_front(&mut self, val: T) {
         self.size += 1;
-        self.root = Self::push_front_aux(self.root.take(), val);
+        self.root = Some(P::fix_root(Self::push_front_aux(self.root.take(), val)));
     }
 
-    fn pop_front_aux(mut node: Box<TreeNode<T>>) -> (Link<T>, Option<T>) {
+    fn pop_front_aux(mut node: Box<TreeNode<T, P>>) -> (Link<T, P>, Option<T>) {
         match node.left {
             None => (node.right, Some(node.val)),
-            Some(next) => {
+            Some(_) => {
+                node = P::descend_left(node);
                 node.num_to_left -= 1;
-                let (left, res) = Self::pop_front_aux(next);
+                let (left, res) = Self::pop_front_aux(node.left.take().unwrap());
                 node.left = left;
-                (Some(node), res)
+                (Some(P::rebalance(node)), res)
             }
         }
     }
@@ -106,37 +144,38 @@ impl<T> RecursiveTreeList<T> {
             None => (None, None),
             Some(node) => {
                 self.size -= 1;
-                Self::pop_front_aux(node)
+                Self::pop_front_aux(P::begin_remove(node))
             }
         };
 
-        self.root = root;
+        self.root = root.map(P::fix_root);
         res
     }
 
-    fn push_back_aux(node: Link<T>, val: T) -> Link<T> {
+    fn push_back_aux(node: Link<T, P>, val: T) -> Box<TreeNode<T, P>> {
         match node {
-            None => Some(Box::new(TreeNode::new(val))),
+            None => Box::new(TreeNode::new(val)),
             Some(x) => {
                 let mut x = x;
-                x.right = Self::push_back_aux(x.right, val);
-                Some(x)
+                x.right = Some(Self::push_back_aux(x.right, val));
+                P::rebalance(x)
             }
         }
     }
 
     pub fn push_back(&mut self, val: T) {
         self.size += 1;
-        self.root = Self::push_back_aux(self.root.take(), val);
+        self.root = Some(P::fix_root(Self::push_back_aux(self.root.take(), val)));
     }
 
-    fn pop_back_aux(mut node: Box<TreeNode<T>>) -> (Link<T>, Option<T>) {
+    fn pop_back_aux(node: Box<TreeNode<T, P>>) -> (Link<T, P>, Option<T>) {
+        let mut node = P::descend_right(node);
         match node.right {
             None => (node.left, Some(node.val)),
             Some(next) => {
                 let (right, res) = Self::pop_back_aux(next);
                 node.right = right;
-                (Some(node), res)
+                (Some(P::rebalance(node)), res)
             }
         }
     }
@@ -146,27 +185,26 @@ impl<T> RecursiveTreeList<T> {
             None => (None, None),
             Some(node) => {
                 self.size -= 1;
-                Self::pop_back_aux(node)
+                Self::pop_back_aux(P::begin_remove(node))
             }
         };
 
-        self.root = root;
+        self.root = root.map(P::fix_root);
         res
     }
 
-    fn insert_aux(node: Link<T>, index: usize, val: T) -> Link<T> {
+    fn insert_aux(node: Link<T, P>, index: usize, val: T) -> Box<TreeNode<T, P>> {
         match node {
-            None => Some(Box::new(TreeNode::new(val))),
+            None => Box::new(TreeNode::new(val)),
             Some(x) => {
                 let mut x = x;
                 if index <= x.num_to_left {
                     x.num_to_left += 1;
-                    x.left = Self::insert_aux(x.left, index, val);
-                    Some(x)
+                    x.left = Some(Self::insert_aux(x.left, index, val));
                 } else {
-                    x.right = Self::insert_aux(x.right, index - x.num_to_left - 1, val);
-                    Some(x)
+                    x.right = Some(Self::insert_aux(x.right, index - x.num_to_left - 1, val));
                 }
+                P::rebalance(x)
             }
         }
     }
@@ -176,36 +214,40 @@ impl<T> RecursiveTreeList<T> {
             panic!("Index out of bounds!");
         } else {
             self.size += 1;
-            self.root = Self::insert_aux(self.root.take(), index, val);
+            self.root = Some(P::fix_root(Self::insert_aux(self.root.take(), index, val)));
         }
     }
 
-    fn remove_aux(mut node: Box<TreeNode<T>>, mut index: usize) -> (Link<T>, T) {
-        match index.cmp(&node.num_to_left) {
-            Ordering::Less => {
-                node.num_to_left -= 1;
-                let (left, res) = Self::remove_aux(node.left.unwrap(), index);
+    fn remove_aux(node: Box<TreeNode<T, P>>, mut index: usize) -> (Link<T, P>, T) {
+        if index < node.num_to_left {
+            let mut node = P::descend_left(node);
+            node.num_to_left -= 1;
+            let (left, res) = Self::remove_aux(node.left.take().unwrap(), index);
+            node.left = left;
+            return (Some(P::rebalance(node)), res);
+        }
+
+        let mut node = P::descend_right(node);
+        match (
+            index.cmp(&node.num_to_left),
+            node.left.take(),
+            node.right.take(),
+        ) {
+            (Ordering::Equal, left, None) => (left, node.val),
+            (Ordering::Equal, left, Some(right)) => {
                 node.left = left;
-                (Some(node), res)
+                let (right, succ) = Self::pop_front_aux(right);
+                node.right = right;
+                let res = std::mem::replace(&mut node.val, succ.unwrap());
+                (Some(P::rebalance(node)), res)
             }
-            Ordering::Greater => {
+            (_, left, right) => {
                 index -= node.num_to_left + 1;
-                let (right, res) = Self::remove_aux(node.right.unwrap(), index);
+                node.left = left;
+                let (right, res) = Self::remove_aux(right.unwrap(), index);
                 node.right = right;
-                (Some(node), res)
+                (Some(P::rebalance(node)), res)
             }
-            Ordering::Equal => match (node.left.take(), node.right.take()) {
-                (None, None) => (None, node.val),
-                (left, None) => (left, node.val),
-                (None, right) => (right, node.val),
-                (left, Some(right)) => {
-                    node.left = left;
-                    let (right, succ) = Self::pop_front_aux(right);
-                    node.right = right;
-                    let res = std::mem::replace(&mut node.val, succ.unwrap());
-                    (Some(node), res)
-                }
-            },
         }
     }
 
@@ -214,15 +256,62 @@ impl<T> RecursiveTreeList<T> {
             panic!("Index out of bounds!");
         } else {
             self.size -= 1;
-            let (root, res) = Self::remove_aux(self.root.take().unwrap(), index);
-            self.root = root;
+            let (root, res) = Self::remove_aux(P::begin_remove(self.root.take().unwrap()), index);
+            self.root = root.map(P::fix_root);
             res
         }
     }
 
+    pub fn try_insert(&mut self, index: usize, val: T) -> Result<(), TreeListError> {
+        if index > self.size {
+            Err(TreeListError {
+                index,
+                len: self.size,
+            })
+        } else {
+            self.insert(index, val);
+            Ok(())
+        }
+    }
+
+    pub fn try_remove(&mut self, index: usize) -> Result<T, TreeListError> {
+        if index >= self.size {
+            Err(TreeListError {
+                index,
+                len: self.size,
+            })
+        } else {
+            Ok(self.remove(index))
+        }
+    }
+
+    pub fn swap(&mut self, a: usize, b: usize) {
+        if let Err(err) = self.try_swap(a, b) {
+            panic!("{}", err);
+        }
+    }
+
+    // Swaps values in place, so unlike a remove and reinsert it never restructures the tree.
+    pub fn try_swap(&mut self, a: usize, b: usize) -> Result<(), TreeListError> {
+        for &index in [a, b].iter() {
+            if index >= self.size {
+                return Err(TreeListError {
+                    index,
+                    len: self.size,
+                });
+            }
+        }
+
+        if a != b {
+            let root = self.root.as_mut().unwrap();
+            TreeNode::swap(root, std::cmp::min(a, b), std::cmp::max(a, b));
+        }
+        Ok(())
+    }
+
     pub fn clear(&mut self) {
         self.size = 0;
-        self.root = None;
+        TreeNode::free(self.root.take());
     }
 
     pub fn len(&self) -> usize {
@@ -233,48 +322,161 @@ impl<T> RecursiveTreeList<T> {
         self.size == 0
     }
 
-    pub fn iter(&self) -> Iter<T> {
-        let mut curr = &self.root;
-        let mut stack: Vec<&TreeNode<T>> = Vec::new();
-        while let Some(ref node) = curr {
-            stack.push(node);
-            curr = &node.left;
+    pub fn iter(&self) -> Iter<'_, T, P> {
+        Iter::new(&self.root, self.size)
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, P> {
+        IterMut::new(&mut self.root, self.size)
+    }
+}
+
+impl<T, P: BalancePolicy> IntoIterator for RecursiveTreeList<T, P> {
+    type Item = T;
+    type IntoIter = IntoIter<T, P>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        let len = std::mem::replace(&mut self.size, 0);
+        IntoIter::new(self.root.take(), len)
+    }
+}
+
+impl<'a, T, P: BalancePolicy> IntoIterator for &'a RecursiveTreeList<T, P> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, P>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, P: BalancePolicy> IntoIterator for &'a mut RecursiveTreeList<T, P> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T, P>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, P: BalancePolicy> FromIterator<T> for RecursiveTreeList<T, P> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_vec(iter.into_iter().collect())
+    }
+}
+
+// The new elements are built into a tree of their own, which is then joined on in one step.
+impl<T, P: BalancePolicy> Extend<T> for RecursiveTreeList<T, P> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        let mut iter = iter.into_iter().collect::<Vec<T>>().into_iter();
+        if let Some(val) = iter.next() {
+            let len = iter.len();
+            let right = P::build(&mut iter, len);
+            let mid = Box::new(TreeNode::new(val));
+            let root = P::join(self.root.take(), self.size, mid, right);
+            self.root = Some(P::fix_root(root));
+            self.size += len + 1;
         }
+    }
+}
 
-        Iter { stack }
+impl<'a, T: Copy + 'a, P: BalancePolicy> Extend<&'a T> for RecursiveTreeList<T, P> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
     }
 }
 
-impl<T> Drop for RecursiveTreeList<T> {
-    fn drop(&mut self) {
-        self.clear();
+impl<T, P: BalancePolicy> From<Vec<T>> for RecursiveTreeList<T, P> {
+    fn from(vec: Vec<T>) -> Self {
+        Self::from_vec(vec)
     }
 }
 
-pub struct Iter<'a, T> {
-    stack: Vec<&'a TreeNode<T>>,
+impl<T, P: BalancePolicy, const N: usize> From<[T; N]> for RecursiveTreeList<T, P> {
+    fn from(arr: [T; N]) -> Self {
+        Self::from_exact_iter(IntoIterator::into_iter(arr))
+    }
 }
 
-impl<'a, T> Iterator for Iter<'a, T> {
-    type Item = &'a T;
+impl<T, P: BalancePolicy> From<RecursiveTreeList<T, P>> for Vec<T> {
+    fn from(list: RecursiveTreeList<T, P>) -> Self {
+        list.into_iter().collect()
+    }
+}
 
-    fn next(&mut self) -> Option<Self::Item> {
-        let next = self.stack.pop();
+impl<T: Clone, P: BalancePolicy> Clone for RecursiveTreeList<T, P> {
+    fn clone(&self) -> Self {
+        Self::from_exact_iter(self.iter().cloned())
+    }
+}
 
-        next.map(|node| {
-            let mut curr = &node.right;
-            while let Some(ref x) = curr {
-                self.stack.push(x);
-                curr = &x.left;
-            }
-            &node.val
-        })
+impl<T: fmt::Debug, P: BalancePolicy> fmt::Debug for RecursiveTreeList<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// Comparisons and hashing go by the elements alone, so lists that hold the same sequence are
+// equal however their trees happen to be shaped.
+impl<T: PartialEq, P: BalancePolicy> PartialEq for RecursiveTreeList<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, P: BalancePolicy> Eq for RecursiveTreeList<T, P> {}
+
+impl<T: PartialOrd, P: BalancePolicy> PartialOrd for RecursiveTreeList<T, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord, P: BalancePolicy> Ord for RecursiveTreeList<T, P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash, P: BalancePolicy> Hash for RecursiveTreeList<T, P> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.size.hash(state);
+        for val in self {
+            val.hash(state);
+        }
+    }
+}
+
+impl<T, P: BalancePolicy> Index<usize> for RecursiveTreeList<T, P> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("Index out of bounds!")
+    }
+}
+
+impl<T, P: BalancePolicy> IndexMut<usize> for RecursiveTreeList<T, P> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("Index out of bounds!")
+    }
+}
+
+impl<T, P: BalancePolicy> Drop for RecursiveTreeList<T, P> {
+    fn drop(&mut self) {
+        self.clear();
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::RecursiveTreeList;
+    use super::{RecursiveTreeList, TreeListError};
+    use crate::balance::tests::{depth, Validate};
+    use crate::balance::{Avl, RedBlack, Unbalanced, WeightBalanced};
+    use crate::node::TreeNode;
+
+    fn check<T, P: Validate>(tree: &RecursiveTreeList<T, P>) {
+        assert_eq!(P::validate(&tree.root), tree.len());
+    }
 
     #[test]
     fn test_empty_tree() {
@@ -495,4 +697,329 @@ mod tests {
             ['b', 'd', 'f', 'c', 'e'].to_vec()
         );
     }
+
+    fn push_back_balanced<P: Validate>(max_depth: usize) {
+        let mut tree: RecursiveTreeList<usize, P> = RecursiveTreeList::with_policy();
+
+        for i in 0..1000 {
+            tree.push_back(i);
+            check(&tree);
+        }
+
+        assert!(depth(&tree.root) <= max_depth);
+        assert_eq!(tree.get(500), Some(&500));
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<usize>>(),
+            (0..1000).collect::<Vec<usize>>()
+        );
+    }
+
+    #[test]
+    fn test_push_back_balanced() {
+        push_back_balanced::<Avl>(15);
+        push_back_balanced::<WeightBalanced>(20);
+        push_back_balanced::<RedBlack>(20);
+    }
+
+    fn push_front_balanced<P: Validate>(max_depth: usize) {
+        let mut tree: RecursiveTreeList<usize, P> = RecursiveTreeList::with_policy();
+
+        for i in 0..1000 {
+            tree.push_front(i);
+        }
+
+        check(&tree);
+        assert!(depth(&tree.root) <= max_depth);
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<usize>>(),
+            (0..1000).rev().collect::<Vec<usize>>()
+        );
+    }
+
+    #[test]
+    fn test_push_front_balanced() {
+        push_front_balanced::<Avl>(15);
+        push_front_balanced::<WeightBalanced>(20);
+        push_front_balanced::<RedBlack>(20);
+    }
+
+    fn insert_remove<P: Validate>() {
+        let mut tree: RecursiveTreeList<usize, P> = RecursiveTreeList::with_policy();
+        let mut vec: Vec<usize> = Vec::new();
+
+        for i in 0..1000 {
+            let index = (i * 7919) % (vec.len() + 1);
+            tree.insert(index, i);
+            vec.insert(index, i);
+        }
+        check(&tree);
+
+        for i in 0..600 {
+            let index = (i * 104_729) % vec.len();
+            assert_eq!(tree.remove(index), vec.remove(index));
+            check(&tree);
+        }
+
+        while let Some(val) = tree.pop_front() {
+            assert_eq!(val, vec.remove(0));
+            assert_eq!(tree.pop_back(), vec.pop());
+            check(&tree);
+        }
+        assert!(vec.is_empty());
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn test_insert_remove_policies() {
+        insert_remove::<Unbalanced>();
+        insert_remove::<Avl>();
+        insert_remove::<WeightBalanced>();
+        insert_remove::<RedBlack>();
+    }
+
+    #[test]
+    fn test_unbalanced() {
+        let mut tree: RecursiveTreeList<usize, Unbalanced> = RecursiveTreeList::with_policy();
+
+        for i in 0..100 {
+            tree.push_back(i);
+        }
+
+        check(&tree);
+        assert_eq!(depth(&tree.root), 100);
+    }
+
+    #[test]
+    fn test_drop_deep_tree() {
+        let mut tree: RecursiveTreeList<usize, Unbalanced> = RecursiveTreeList::with_policy();
+
+        // Pushing would recurse as deep as the tree, so chain the nodes up by hand.
+        for i in 0..1_000_000 {
+            let mut node = Box::new(TreeNode::new(i));
+            node.num_to_left = i;
+            node.left = tree.root.take();
+            tree.root = Some(node);
+            tree.size += 1;
+        }
+
+        assert_eq!(tree.get(0), Some(&0));
+        tree.clear();
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn test_iter_double_ended() {
+        let mut tree: RecursiveTreeList<usize> = RecursiveTreeList::new();
+        for i in 0..100 {
+            tree.push_front(i);
+        }
+
+        let mut iter = tree.iter();
+        assert_eq!(iter.len(), 100);
+        assert_eq!(iter.next_back(), Some(&0));
+        assert_eq!(iter.nth(49), Some(&50));
+        assert_eq!(iter.nth_back(9), Some(&10));
+        assert_eq!(
+            iter.rev().copied().collect::<Vec<usize>>(),
+            (11..50).collect::<Vec<usize>>()
+        );
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut tree: RecursiveTreeList<usize> = RecursiveTreeList::new();
+        for i in 0..100 {
+            tree.push_back(i);
+        }
+
+        let iter = tree.iter_mut();
+        assert_eq!(iter.len(), 100);
+        for val in iter {
+            *val *= 2;
+        }
+
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<usize>>(),
+            (0..100).map(|i| i * 2).collect::<Vec<usize>>()
+        );
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let mut tree: RecursiveTreeList<String> = RecursiveTreeList::new();
+        for i in 0..100 {
+            tree.push_back(i.to_string());
+        }
+
+        let mut iter = tree.into_iter();
+        assert_eq!(iter.next(), Some("0".to_string()));
+        assert_eq!(iter.next_back(), Some("99".to_string()));
+        assert_eq!(iter.len(), 98);
+        assert_eq!(
+            iter.rev().take(3).collect::<Vec<String>>(),
+            ["98", "97", "96"]
+        );
+    }
+
+    fn extend<P: Validate>() {
+        let mut tree: RecursiveTreeList<usize, P> = RecursiveTreeList::with_policy();
+        let mut vec: Vec<usize> = Vec::new();
+
+        for i in 0..50 {
+            let more = (i * 7919) % 40;
+            tree.extend(vec.len()..vec.len() + more);
+            vec.extend(vec.len()..vec.len() + more);
+            check(&tree);
+        }
+        assert_eq!(tree.iter().copied().collect::<Vec<usize>>(), vec);
+    }
+
+    #[test]
+    fn test_extend_policies() {
+        extend::<Unbalanced>();
+        extend::<Avl>();
+        extend::<WeightBalanced>();
+        extend::<RedBlack>();
+    }
+
+    #[test]
+    fn test_std_traits() {
+        let mut tree: RecursiveTreeList<usize> = (0..10).collect();
+        tree.extend(10..15);
+        tree.extend(&[15, 16]);
+        tree[0] = 100;
+        assert_eq!(tree[0], 100);
+        assert_eq!(tree[16], 16);
+
+        for val in &mut tree {
+            *val += 1;
+        }
+        assert_eq!((&tree).into_iter().sum::<usize>(), 100 + 136 + 17);
+
+        let copy = tree.clone();
+        assert_eq!(copy, tree);
+        assert_eq!(
+            format!("{:?}", RecursiveTreeList::<usize>::from([1, 2])),
+            "[1, 2]"
+        );
+        assert_eq!(
+            Vec::from(copy),
+            tree.iter().copied().collect::<Vec<usize>>()
+        );
+
+        // Equal sequences built in different orders give differently shaped trees.
+        let mut front: RecursiveTreeList<usize> = RecursiveTreeList::new();
+        for i in (0..100).rev() {
+            front.push_front(i);
+        }
+        let back: RecursiveTreeList<usize> =
+            RecursiveTreeList::from((0..100).collect::<Vec<usize>>());
+        assert_eq!(front, back);
+
+        let mut hashes = std::collections::HashSet::new();
+        hashes.insert(front);
+        assert!(hashes.contains(&back));
+
+        let shorter: RecursiveTreeList<usize> = RecursiveTreeList::from([0, 1, 2]);
+        let larger: RecursiveTreeList<usize> = RecursiveTreeList::from([0, 2]);
+        assert!(shorter < back);
+        assert!(larger > back);
+        assert_eq!(shorter.cmp(&shorter.clone()), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn test_from_vec() {
+        for len in 0..300 {
+            let tree: RecursiveTreeList<usize> = RecursiveTreeList::from_vec((0..len).collect());
+            check(&tree);
+            assert_eq!(
+                tree.iter().copied().collect::<Vec<usize>>(),
+                (0..len).collect::<Vec<usize>>()
+            );
+        }
+
+        let mut tree: RecursiveTreeList<usize, Avl> = (0..1000).collect();
+        check(&tree);
+        assert!(depth(&tree.root) <= 10);
+        tree.push_front(0);
+        check(&tree);
+    }
+
+    #[test]
+    fn test_get_out_of_bounds() {
+        let mut tree: RecursiveTreeList<usize> = RecursiveTreeList::new();
+        assert_eq!(tree.get(0), None);
+        assert_eq!(tree.get_mut(0), None);
+
+        tree.push_back(0);
+        tree.push_back(1);
+        assert_eq!(tree.get(2), None);
+        assert_eq!(tree.get_mut(2), None);
+        assert_eq!(tree.get(usize::MAX), None);
+    }
+
+    #[test]
+    fn test_try_methods() {
+        let mut tree: RecursiveTreeList<usize> = (0..10).collect();
+
+        let err = tree.try_insert(11, 0).unwrap_err();
+        assert_eq!(err, TreeListError { index: 11, len: 10 });
+        assert_eq!(
+            err.to_string(),
+            "index 11 out of bounds for list of length 10"
+        );
+        assert_eq!(tree.try_insert(10, 10), Ok(()));
+
+        assert_eq!(
+            tree.try_remove(11),
+            Err(TreeListError { index: 11, len: 11 })
+        );
+        assert_eq!(tree.try_remove(10), Ok(10));
+
+        assert_eq!(
+            tree.try_swap(3, 10),
+            Err(TreeListError { index: 10, len: 10 })
+        );
+        assert_eq!(tree.try_swap(3, 3), Ok(()));
+        assert_eq!(tree.len(), 10);
+    }
+
+    #[test]
+    fn test_swap() {
+        let mut tree: RecursiveTreeList<usize> = (0..100).collect();
+        let mut vec: Vec<usize> = (0..100).collect();
+
+        for i in 0..200 {
+            let (a, b) = ((i * 7919) % 100, (i * 104_729) % 100);
+            tree.swap(a, b);
+            vec.swap(a, b);
+        }
+
+        check(&tree);
+        assert_eq!(tree.iter().copied().collect::<Vec<usize>>(), vec);
+    }
+
+    #[test]
+    fn test_binary_search() {
+        let vec: Vec<usize> = (0..200).map(|i| i / 3 * 2).collect();
+        let tree: RecursiveTreeList<usize> = vec.iter().copied().collect();
+
+        for x in 0..140 {
+            match (tree.binary_search(&x), vec.binary_search(&x)) {
+                (Ok(i), Ok(_)) => assert_eq!(tree[i], x),
+                (res, expected) => assert_eq!(res, expected),
+            }
+            assert_eq!(
+                tree.partition_point(|&val| val < x),
+                vec.partition_point(|&val| val < x)
+            );
+        }
+
+        let pairs: RecursiveTreeList<(usize, char)> =
+            RecursiveTreeList::from([(1, 'a'), (3, 'b'), (5, 'c')]);
+        assert_eq!(pairs.binary_search_by_key(&3, |&(key, _)| key), Ok(1));
+        assert_eq!(pairs.binary_search_by_key(&4, |&(key, _)| key), Err(2));
+        assert_eq!(pairs.binary_search_by(|&(_, c)| c.cmp(&'z')), Err(3));
+        assert_eq!(RecursiveTreeList::<usize>::new().binary_search(&0), Err(0));
+    }
 }
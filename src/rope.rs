@@ -0,0 +1,512 @@
+use crate::augmented_tree_list::{self, AugmentedTreeList, Monoid};
+use crate::balance::Avl;
+use crate::tree_list::resolve_range;
+use std::fmt;
+use std::ops::RangeBounds;
+
+// Chunks are kept at or below this many bytes, and at or above the minimum unless there is only
+// one. Any text longer than `MAX_CHUNK` can be cut at a char boundary into pieces within these
+// limits, which a minimum of half the maximum would not always allow.
+const MAX_CHUNK: usize = 1024;
+const MIN_CHUNK: usize = MAX_CHUNK / 4;
+
+/// The size of a run of text in each of the units a `Rope` can be indexed by.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TextSummary {
+    pub bytes: usize,
+    pub chars: usize,
+    pub newlines: usize,
+}
+
+// A piece of the text along with its summary, which is counted once when the chunk is made rather
+// than every time the tree recombines the summaries above it.
+#[derive(Debug)]
+struct Chunk {
+    text: String,
+    summary: TextSummary,
+}
+
+impl Chunk {
+    fn new(text: String) -> Self {
+        let summary = TextSummary {
+            bytes: text.len(),
+            chars: text.chars().count(),
+            newlines: text.bytes().filter(|&b| b == b'\n').count(),
+        };
+        Chunk { text, summary }
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy)]
+struct TextMetrics;
+
+impl Monoid<Chunk> for TextMetrics {
+    type Summary = TextSummary;
+
+    fn identity() -> TextSummary {
+        TextSummary::default()
+    }
+
+    fn measure(chunk: &Chunk) -> TextSummary {
+        chunk.summary
+    }
+
+    fn combine(left: &TextSummary, right: &TextSummary) -> TextSummary {
+        TextSummary {
+            bytes: left.bytes + right.bytes,
+            chars: left.chars + right.chars,
+            newlines: left.newlines + right.newlines,
+        }
+    }
+}
+
+// Cuts `text` into chunks of at most `MAX_CHUNK` bytes, never splitting a character. Each cut
+// leaves at least `MIN_CHUNK` bytes behind, so only text shorter than that gives a short chunk.
+fn split_chunks(mut text: &str) -> Vec<Chunk> {
+    let mut chunks = Vec::new();
+    while text.len() > MAX_CHUNK {
+        let mut at = std::cmp::min(MAX_CHUNK, text.len() - MIN_CHUNK);
+        while !text.is_char_boundary(at) {
+            at -= 1;
+        }
+        let (chunk, rest) = text.split_at(at);
+        chunks.push(Chunk::new(chunk.to_string()));
+        text = rest;
+    }
+    if !text.is_empty() {
+        chunks.push(Chunk::new(text.to_string()));
+    }
+    chunks
+}
+
+// The byte offset of the `chars`th character of `text`, or its length if there are fewer.
+fn byte_offset(text: &str, chars: usize) -> usize {
+    text.char_indices()
+        .nth(chars)
+        .map_or(text.len(), |(i, _)| i)
+}
+
+// Text is split into chunks of `MIN_CHUNK` to `MAX_CHUNK` bytes, held in an `AugmentedTreeList`
+// that sums their lengths in bytes, chars and newlines. Locating a position in any of those units
+// is a `find_prefix` to pick the chunk followed by a scan within it.
+#[derive(Debug, Default)]
+pub struct Rope {
+    chunks: AugmentedTreeList<Chunk, TextMetrics>,
+}
+
+impl Rope {
+    pub fn new() -> Self {
+        Rope {
+            chunks: AugmentedTreeList::new(),
+        }
+    }
+
+    // The index of the chunk holding the character at `char_idx`, along with the summary of the
+    // text before that chunk. The end of the text belongs to the last chunk, and the rope must
+    // not be empty.
+    fn chunk_at_char(&self, char_idx: usize) -> (usize, TextSummary) {
+        let index = self
+            .chunks
+            .find_prefix(|summary| summary.chars > char_idx)
+            .unwrap_or(self.chunks.len() - 1);
+        (index, self.chunks.fold(..index))
+    }
+
+    // Replaces the chunk at `index` and the ones after it with `text`, cut into new chunks.
+    fn insert_chunks(&mut self, index: usize, text: &str) {
+        for (i, piece) in split_chunks(text).into_iter().enumerate() {
+            self.chunks.insert(index + i, piece);
+        }
+    }
+
+    // Merges the chunk at `index` into a neighbour for as long as it is too short, cutting the
+    // result in two again if it's too long. The neighbour is long enough, so both halves are.
+    fn merge_small(&mut self, mut index: usize) {
+        while self.chunks.len() > 1 && self.chunks.get(index).unwrap().text.len() < MIN_CHUNK {
+            index = std::cmp::min(index, self.chunks.len() - 2);
+            let next = self.chunks.remove(index + 1).text;
+            let mut chunk = self.chunks.remove(index).text;
+            chunk.push_str(&next);
+            self.insert_chunks(index, &chunk);
+        }
+    }
+
+    pub fn len_bytes(&self) -> usize {
+        self.chunks.summary().bytes
+    }
+
+    pub fn len_chars(&self) -> usize {
+        self.chunks.summary().chars
+    }
+
+    /// The number of lines, which is one more than the number of newlines. Text ending in a
+    /// newline is followed by an empty last line.
+    pub fn len_lines(&self) -> usize {
+        self.chunks.summary().newlines + 1
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.chunks.is_empty()
+    }
+
+    /// Inserts `text` so that it starts at `char_idx`. Only the chunk at `char_idx` is rebuilt,
+    /// and each of the `m / MAX_CHUNK + 1` chunks it becomes is measured once as it goes into
+    /// the tree, so inserting `m` bytes takes `O((m / MAX_CHUNK + 1) log n + m)` time plus a
+    /// scan of the rebuilt chunk.
+    pub fn insert_str(&mut self, char_idx: usize, text: &str) {
+        if char_idx > self.len_chars() {
+            panic!("Index out of bounds!");
+        } else if self.is_empty() {
+            self.insert_chunks(0, text);
+        } else if !text.is_empty() {
+            let (index, before) = self.chunk_at_char(char_idx);
+            let mut chunk = self.chunks.remove(index).text;
+            chunk.insert_str(byte_offset(&chunk, char_idx - before.chars), text);
+            self.insert_chunks(index, &chunk);
+        }
+    }
+
+    /// Removes the characters in `range`. Chunks inside the range are dropped whole, so removing
+    /// `k` characters takes `O((k / MAX_CHUNK + 1) log n)` time plus a scan of each end chunk.
+    pub fn remove<R: RangeBounds<usize>>(&mut self, range: R) {
+        let (start, end) = resolve_range(range, self.len_chars());
+        let mut remaining = end - start;
+
+        while remaining > 0 {
+            let (index, before) = self.chunk_at_char(start);
+            let mut chunk = self.chunks.remove(index).text;
+            let from = byte_offset(&chunk, start - before.chars);
+            let to = from + byte_offset(&chunk[from..], remaining);
+            remaining -= chunk[from..to].chars().count();
+            chunk.replace_range(from..to, "");
+            if !chunk.is_empty() {
+                self.chunks.insert(index, Chunk::new(chunk));
+            }
+        }
+
+        // Only the chunks either side of the cut can have shrunk.
+        if start < end && !self.is_empty() {
+            for &char_idx in [start.saturating_sub(1), start].iter() {
+                let (index, _) = self.chunk_at_char(char_idx);
+                self.merge_small(index);
+            }
+        }
+    }
+
+    pub fn char_to_byte(&self, char_idx: usize) -> usize {
+        if char_idx > self.len_chars() {
+            panic!("Index out of bounds!");
+        } else if char_idx == self.len_chars() {
+            self.len_bytes()
+        } else {
+            let (index, before) = self.chunk_at_char(char_idx);
+            let chunk = &self.chunks.get(index).unwrap().text;
+            before.bytes + byte_offset(chunk, char_idx - before.chars)
+        }
+    }
+
+    /// The index of the first character of line `line_idx`, counting from zero.
+    pub fn line_to_char(&self, line_idx: usize) -> usize {
+        if line_idx >= self.len_lines() {
+            panic!("Index out of bounds!");
+        } else if line_idx == 0 {
+            0
+        } else {
+            // Line `line_idx` starts just after newline number `line_idx`.
+            let index = self
+                .chunks
+                .find_prefix(|summary| summary.newlines >= line_idx)
+                .unwrap();
+            let before = self.chunks.fold(..index);
+            let chunk = &self.chunks.get(index).unwrap().text;
+            let newlines = line_idx - before.newlines;
+            let chars = chunk
+                .chars()
+                .enumerate()
+                .filter(|&(_, c)| c == '\n')
+                .nth(newlines - 1)
+                .map(|(i, _)| i + 1)
+                .unwrap();
+            before.chars + chars
+        }
+    }
+
+    /// The characters in `range`, copied out into a `String`.
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> String {
+        let (start, end) = resolve_range(range, self.len_chars());
+        let mut res = String::new();
+        if start == end {
+            return res;
+        }
+
+        let (index, before) = self.chunk_at_char(start);
+        let mut skip = start - before.chars;
+        let mut remaining = end - start;
+        for chunk in self.chunks().skip(index) {
+            let from = byte_offset(chunk, skip);
+            let to = from + byte_offset(&chunk[from..], remaining);
+            res.push_str(&chunk[from..to]);
+            remaining -= chunk[from..to].chars().count();
+            skip = 0;
+            if remaining == 0 {
+                break;
+            }
+        }
+        res
+    }
+
+    /// The lines of the text, without their newlines. There are always `len_lines()` of them.
+    pub fn lines(&self) -> Lines<'_> {
+        Lines {
+            chunks: self.chunks.iter(),
+            rest: "",
+            done: false,
+        }
+    }
+
+    /// The pieces the text is stored in, in order.
+    pub fn chunks(&self) -> impl Iterator<Item = &str> {
+        self.chunks.iter().map(|chunk| chunk.text.as_str())
+    }
+}
+
+impl From<&str> for Rope {
+    fn from(text: &str) -> Self {
+        let mut rope = Rope::new();
+        rope.insert_str(0, text);
+        rope
+    }
+}
+
+impl fmt::Display for Rope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for chunk in self.chunks() {
+            f.write_str(chunk)?;
+        }
+        Ok(())
+    }
+}
+
+// Writing appends to the end of the text.
+impl fmt::Write for Rope {
+    fn write_str(&mut self, text: &str) -> fmt::Result {
+        self.insert_str(self.len_chars(), text);
+        Ok(())
+    }
+}
+
+pub struct Lines<'a> {
+    chunks: augmented_tree_list::Iter<'a, Chunk, Avl, TextMetrics>,
+    rest: &'a str,
+    done: bool,
+}
+
+impl<'a> Iterator for Lines<'a> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let mut line = String::new();
+        loop {
+            if self.rest.is_empty() {
+                match self.chunks.next() {
+                    Some(chunk) => self.rest = &chunk.text,
+                    None => {
+                        self.done = true;
+                        return Some(line);
+                    }
+                }
+            }
+
+            match self.rest.find('\n') {
+                Some(i) => {
+                    line.push_str(&self.rest[..i]);
+                    self.rest = &self.rest[i + 1..];
+                    return Some(line);
+                }
+                None => {
+                    line.push_str(self.rest);
+                    self.rest = "";
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Chunk, Rope, MAX_CHUNK, MIN_CHUNK};
+    use std::fmt::Write;
+
+    // Asserts that every chunk is non-empty, within bounds and correctly summarised, and that the
+    // rope reads back as `expected`.
+    fn check(rope: &Rope, expected: &str) {
+        let single = rope.chunks.len() == 1;
+        for chunk in rope.chunks.iter() {
+            assert!(!chunk.text.is_empty() && chunk.text.len() <= MAX_CHUNK);
+            assert!(single || chunk.text.len() >= MIN_CHUNK);
+            assert_eq!(chunk.summary, Chunk::new(chunk.text.clone()).summary);
+        }
+        assert_eq!(rope.to_string(), expected);
+        assert_eq!(rope.len_bytes(), expected.len());
+        assert_eq!(rope.len_chars(), expected.chars().count());
+        assert_eq!(rope.len_lines(), expected.split('\n').count());
+    }
+
+    // Inserts into a `String` model by char index.
+    fn insert_model(model: &mut String, char_idx: usize, text: &str) {
+        let at = model
+            .char_indices()
+            .nth(char_idx)
+            .map_or(model.len(), |(i, _)| i);
+        model.insert_str(at, text);
+    }
+
+    #[test]
+    fn test_empty_rope() {
+        let rope = Rope::new();
+
+        check(&rope, "");
+        assert_eq!(rope.char_to_byte(0), 0);
+        assert_eq!(rope.line_to_char(0), 0);
+        assert_eq!(rope.slice(..), "");
+        assert_eq!(rope.lines().collect::<Vec<String>>(), [""].to_vec());
+    }
+
+    #[test]
+    fn test_insert_remove() {
+        let mut rope = Rope::from("hello world");
+        rope.insert_str(5, ",");
+        rope.insert_str(12, "!");
+        rope.insert_str(0, "¡");
+        check(&rope, "¡hello, world!");
+
+        rope.remove(1..7);
+        check(&rope, "¡ world!");
+        rope.remove(..);
+        check(&rope, "");
+        rope.insert_str(0, "añb");
+        check(&rope, "añb");
+    }
+
+    #[test]
+    fn test_indexing() {
+        let rope = Rope::from("añ\nbč\n\nd");
+
+        assert_eq!(
+            (0..=8)
+                .map(|i| rope.char_to_byte(i))
+                .collect::<Vec<usize>>(),
+            [0, 1, 3, 4, 5, 7, 8, 9, 10].to_vec()
+        );
+        assert_eq!(
+            (0..4).map(|i| rope.line_to_char(i)).collect::<Vec<usize>>(),
+            [0, 3, 6, 7].to_vec()
+        );
+        assert_eq!(
+            rope.lines().collect::<Vec<String>>(),
+            ["añ", "bč", "", "d"].to_vec()
+        );
+        assert_eq!(rope.slice(1..5), "ñ\nbč");
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of bounds!")]
+    fn test_line_out_of_bounds() {
+        let rope = Rope::from("a\nb");
+        rope.line_to_char(2);
+    }
+
+    #[test]
+    fn test_write() {
+        let mut rope = Rope::new();
+        for i in 0..1000 {
+            writeln!(rope, "line {} ✓", i).unwrap();
+        }
+
+        let expected = (0..1000)
+            .map(|i| format!("line {} ✓\n", i))
+            .collect::<String>();
+        check(&rope, &expected);
+        assert!(rope.chunks().count() > 1);
+        assert_eq!(rope.len_lines(), 1001);
+        assert_eq!(
+            rope.line_to_char(500),
+            expected
+                .split('\n')
+                .take(500)
+                .map(|l| l.chars().count() + 1)
+                .sum()
+        );
+        assert_eq!(rope.lines().nth(734), Some("line 734 ✓".to_string()));
+        assert_eq!(rope.lines().count(), 1001);
+    }
+
+    #[test]
+    fn test_typing_keeps_chunks_full() {
+        let mut model = "a".repeat(MAX_CHUNK);
+        let mut rope = Rope::from(model.as_str());
+
+        for i in 0..2000 {
+            rope.insert_str(i, "b");
+            insert_model(&mut model, i, "b");
+        }
+        check(&rope, &model);
+        assert!(rope.chunks.len() <= model.len() / MIN_CHUNK);
+    }
+
+    #[test]
+    fn test_random_edits() {
+        let mut rope = Rope::new();
+        let mut model = String::new();
+        let pieces = ["a", "añ", "\n", "日本語", "xyz\n\n", "🦀🦀", ""];
+
+        for i in 0..2000 {
+            let len = model.chars().count();
+            if i % 3 == 2 {
+                let start = (i * 7919) % (len + 1);
+                let end = start + (i * 31) % (len - start + 1) % 300;
+                rope.remove(start..end);
+                let from = model
+                    .char_indices()
+                    .nth(start)
+                    .map_or(model.len(), |(i, _)| i);
+                let to = model
+                    .char_indices()
+                    .nth(end)
+                    .map_or(model.len(), |(i, _)| i);
+                model.replace_range(from..to, "");
+            } else {
+                let char_idx = (i * 104_729) % (len + 1);
+                let text = pieces[i % pieces.len()].repeat(i % 40);
+                rope.insert_str(char_idx, &text);
+                insert_model(&mut model, char_idx, &text);
+            }
+        }
+        check(&rope, &model);
+
+        let chars: Vec<(usize, char)> = model.char_indices().collect();
+        for (char_idx, &(byte_idx, _)) in chars.iter().enumerate().step_by(13) {
+            assert_eq!(rope.char_to_byte(char_idx), byte_idx);
+        }
+        let mut line_start = 0;
+        for (line_idx, line) in model.split('\n').enumerate() {
+            assert_eq!(rope.line_to_char(line_idx), line_start);
+            line_start += line.chars().count() + 1;
+        }
+        assert_eq!(
+            rope.lines().collect::<Vec<String>>(),
+            model.split('\n').collect::<Vec<&str>>()
+        );
+
+        let len = chars.len();
+        for start in (0..len).step_by(97) {
+            let end = std::cmp::min(len, start + 1500);
+            let expected: String = model.chars().skip(start).take(end - start).collect();
+            assert_eq!(rope.slice(start..end), expected);
+        }
+    }
+}
@@ -0,0 +1,299 @@
+use crate::balance::{Avl, BalancePolicy};
+use crate::tree_list::{Iter, TreeList};
+use std::ops::{Bound, RangeBounds};
+
+// Keeps its elements in sorted order in a plain `TreeList`, finding positions by comparison.
+// Since every node already knows the size of its left subtree, the list doubles as an
+// order-statistic tree. Equal elements are kept in insertion order.
+#[derive(Debug)]
+pub struct SortedTreeList<T: Ord, P: BalancePolicy = Avl> {
+    list: TreeList<T, P>,
+}
+
+impl<T: Ord, P: BalancePolicy> Default for SortedTreeList<T, P> {
+    fn default() -> Self {
+        Self::with_policy()
+    }
+}
+
+impl<T: Ord> SortedTreeList<T> {
+    pub fn new() -> Self {
+        Self::with_policy()
+    }
+}
+
+impl<T: Ord, P: BalancePolicy> SortedTreeList<T, P> {
+    pub fn with_policy() -> Self {
+        SortedTreeList {
+            list: TreeList::with_policy(),
+        }
+    }
+
+    // The index of the first element not less than `val`.
+    fn lower_bound(&self, val: &T) -> usize {
+        self.list.partition_point(|x| x < val)
+    }
+
+    // The index of the first element greater than `val`.
+    fn upper_bound(&self, val: &T) -> usize {
+        self.list.partition_point(|x| x <= val)
+    }
+
+    // Resolves `range` to the indices of the elements that fall within it.
+    fn bounds<R: RangeBounds<T>>(&self, range: R) -> (usize, usize) {
+        let start = match range.start_bound() {
+            Bound::Included(start) => self.lower_bound(start),
+            Bound::Excluded(start) => self.upper_bound(start),
+            Bound::Unbounded => 0,
+        };
+        let end = match range.end_bound() {
+            Bound::Included(end) => self.upper_bound(end),
+            Bound::Excluded(end) => self.lower_bound(end),
+            Bound::Unbounded => self.list.len(),
+        };
+        (start, std::cmp::max(start, end))
+    }
+
+    /// Inserts `val` after any elements equal to it, returning the index it ended up at.
+    pub fn insert(&mut self, val: T) -> usize {
+        let index = self.upper_bound(&val);
+        self.list.insert(index, val);
+        index
+    }
+
+    /// The number of elements less than `val`, which is also the index of the first element
+    /// equal to it, if there is one.
+    pub fn rank(&self, val: &T) -> usize {
+        self.lower_bound(val)
+    }
+
+    /// The `k`th smallest element, counting from zero.
+    pub fn select(&self, k: usize) -> Option<&T> {
+        self.list.get(k)
+    }
+
+    pub fn contains(&self, val: &T) -> bool {
+        self.list.get(self.lower_bound(val)) == Some(val)
+    }
+
+    /// Removes the first element equal to `val`, if there is one.
+    pub fn remove_value(&mut self, val: &T) -> Option<T> {
+        let index = self.lower_bound(val);
+        if self.list.get(index) == Some(val) {
+            Some(self.list.remove(index))
+        } else {
+            None
+        }
+    }
+
+    /// Removes the `k`th smallest element.
+    pub fn remove_at(&mut self, k: usize) -> Option<T> {
+        self.list.try_remove(k).ok()
+    }
+
+    /// Iterates over the elements within `range`, in order. An empty or inverted range yields
+    /// nothing.
+    pub fn range<R: RangeBounds<T>>(&self, range: R) -> Iter<'_, T, P> {
+        let (start, end) = self.bounds(range);
+        Iter::with_range(&self.list.root, start, end)
+    }
+
+    pub fn count_in_range<R: RangeBounds<T>>(&self, range: R) -> usize {
+        let (start, end) = self.bounds(range);
+        end - start
+    }
+
+    pub fn first(&self) -> Option<&T> {
+        self.list.get(0)
+    }
+
+    pub fn last(&self) -> Option<&T> {
+        self.list
+            .len()
+            .checked_sub(1)
+            .and_then(|i| self.list.get(i))
+    }
+
+    pub fn pop_first(&mut self) -> Option<T> {
+        self.list.pop_front()
+    }
+
+    pub fn pop_last(&mut self) -> Option<T> {
+        self.list.pop_back()
+    }
+
+    pub fn clear(&mut self) {
+        self.list.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T, P> {
+        self.list.iter()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SortedTreeList;
+    use crate::balance::tests::Validate;
+    use crate::balance::{BalancePolicy, RedBlack};
+    use std::ops::Bound;
+
+    fn check<T: Ord, P: Validate + BalancePolicy>(tree: &SortedTreeList<T, P>) {
+        assert_eq!(P::validate(&tree.list.root), tree.len());
+        assert!(tree.iter().zip(tree.iter().skip(1)).all(|(a, b)| a <= b));
+    }
+
+    #[test]
+    fn test_empty_tree() {
+        let tree: SortedTreeList<usize> = SortedTreeList::new();
+
+        assert_eq!(tree.len(), 0);
+        assert_eq!(tree.rank(&5), 0);
+        assert_eq!(tree.select(0), None);
+        assert_eq!(tree.first(), None);
+        assert_eq!(tree.last(), None);
+        assert_eq!(tree.count_in_range(..), 0);
+    }
+
+    #[test]
+    fn test_insert_sorted() {
+        let mut tree: SortedTreeList<char> = SortedTreeList::new();
+
+        assert_eq!(tree.insert('c'), 0);
+        assert_eq!(tree.insert('a'), 0);
+        assert_eq!(tree.insert('d'), 2);
+        assert_eq!(tree.insert('b'), 1);
+        assert_eq!(tree.insert('b'), 2);
+
+        check(&tree);
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<char>>(),
+            ['a', 'b', 'b', 'c', 'd'].to_vec()
+        );
+        assert_eq!(tree.first(), Some(&'a'));
+        assert_eq!(tree.last(), Some(&'d'));
+    }
+
+    #[test]
+    fn test_duplicates_keep_insertion_order() {
+        let mut tree: SortedTreeList<(u8, Ord0)> = SortedTreeList::new();
+        for i in 0..5 {
+            tree.insert((1, Ord0(i)));
+            tree.insert((0, Ord0(i)));
+        }
+
+        let order = tree
+            .iter()
+            .map(|(key, val)| (*key, val.0))
+            .collect::<Vec<_>>();
+        assert_eq!(order[..5], [(0, 0), (0, 1), (0, 2), (0, 3), (0, 4)]);
+        assert_eq!(order[5..], [(1, 0), (1, 1), (1, 2), (1, 3), (1, 4)]);
+    }
+
+    // Compares equal to everything, so that only the key decides the order.
+    #[derive(Debug)]
+    struct Ord0(usize);
+
+    impl PartialEq for Ord0 {
+        fn eq(&self, _: &Self) -> bool {
+            true
+        }
+    }
+
+    impl Eq for Ord0 {}
+
+    impl PartialOrd for Ord0 {
+        fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+            Some(self.cmp(other))
+        }
+    }
+
+    impl Ord for Ord0 {
+        fn cmp(&self, _: &Self) -> std::cmp::Ordering {
+            std::cmp::Ordering::Equal
+        }
+    }
+
+    #[test]
+    fn test_order_statistics_random() {
+        let mut tree: SortedTreeList<usize, RedBlack> = SortedTreeList::with_policy();
+        let mut vec: Vec<usize> = Vec::new();
+
+        for i in 0..1000 {
+            let val = (i * 7919) % 300;
+            tree.insert(val);
+            let index = vec.partition_point(|&x| x <= val);
+            vec.insert(index, val);
+        }
+        for i in 0..300 {
+            let val = (i * 104_729) % 400;
+            let expected = vec.iter().position(|&x| x == val).map(|i| vec.remove(i));
+            assert_eq!(tree.remove_value(&val), expected);
+        }
+        check(&tree);
+
+        for val in 0..310 {
+            assert_eq!(tree.rank(&val), vec.partition_point(|&x| x < val));
+            assert_eq!(tree.contains(&val), vec.contains(&val));
+        }
+        for (k, val) in vec.iter().enumerate() {
+            assert_eq!(tree.select(k), Some(val));
+        }
+        assert_eq!(tree.select(vec.len()), None);
+    }
+
+    #[test]
+    fn test_range() {
+        let mut tree: SortedTreeList<usize> = SortedTreeList::new();
+        for i in 0..100 {
+            tree.insert(i / 2);
+        }
+
+        assert_eq!(
+            tree.range(10..=12).copied().collect::<Vec<usize>>(),
+            [10, 10, 11, 11, 12, 12].to_vec()
+        );
+        assert_eq!(
+            tree.range(..3).rev().copied().collect::<Vec<usize>>(),
+            [2, 2, 1, 1, 0, 0].to_vec()
+        );
+        assert_eq!(tree.count_in_range(10..=12), 6);
+        assert_eq!(tree.count_in_range(10..12), 4);
+        assert_eq!(
+            tree.count_in_range((Bound::Excluded(10), Bound::Unbounded)),
+            78
+        );
+        assert_eq!(tree.count_in_range(60..), 0);
+        let inverted = (Bound::Included(20), Bound::Excluded(10));
+        assert_eq!(tree.count_in_range(inverted), 0);
+        assert_eq!(tree.range(inverted).next(), None);
+        assert_eq!(tree.range(..).len(), 100);
+    }
+
+    #[test]
+    fn test_pop_and_remove_at() {
+        let mut tree: SortedTreeList<usize> = SortedTreeList::new();
+        for &i in [5, 1, 4, 2, 3].iter() {
+            tree.insert(i);
+        }
+
+        assert_eq!(tree.pop_first(), Some(1));
+        assert_eq!(tree.pop_last(), Some(5));
+        assert_eq!(tree.remove_at(1), Some(3));
+        assert_eq!(tree.remove_at(2), None);
+        assert_eq!(tree.remove_value(&3), None);
+        check(&tree);
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<usize>>(),
+            [2, 4].to_vec()
+        );
+    }
+}
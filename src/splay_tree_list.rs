@@ -0,0 +1,330 @@
+use std::cmp::Ordering;
+type Link<T> = Option<Box<TreeNode<T>>>;
+
+#[derive(Debug)]
+struct TreeNode<T> {
+    val: T,
+    num_to_left: usize,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+impl<T> TreeNode<T> {
+    fn new(val: T) -> Self {
+        TreeNode {
+            val,
+            num_to_left: 0,
+            left: None,
+            right: None,
+        }
+    }
+}
+
+// Top-down splay of the subtree `node`, which holds `size` elements, bringing the element at
+// `index` to the root. Splay trees can be arbitrarily deep, so this never recurses: the nodes
+// split off on either side of the search path are collected and reassembled at the end.
+fn splay<T>(mut node: Box<TreeNode<T>>, mut size: usize, mut index: usize) -> Box<TreeNode<T>> {
+    // Nodes smaller than the target, each missing its right subtree.
+    let mut left_nodes: Vec<Box<TreeNode<T>>> = Vec::new();
+    // Nodes larger than the target, each missing its left subtree, along with their sizes.
+    let mut right_nodes: Vec<(Box<TreeNode<T>>, usize)> = Vec::new();
+
+    loop {
+        match index.cmp(&node.num_to_left) {
+            Ordering::Less => {
+                if index < node.left.as_ref().unwrap().num_to_left {
+                    let mut left = node.left.take().unwrap();
+                    node.left = left.right.take();
+                    node.num_to_left -= left.num_to_left + 1;
+                    left.right = Some(node);
+                    node = left;
+                }
+
+                let left = node.left.take().unwrap();
+                let left_size = node.num_to_left;
+                right_nodes.push((node, size - left_size));
+                node = left;
+                size = left_size;
+            }
+            Ordering::Greater => {
+                let right_index = index - node.num_to_left - 1;
+                if right_index > node.right.as_ref().unwrap().num_to_left {
+                    let mut right = node.right.take().unwrap();
+                    node.right = right.left.take();
+                    right.num_to_left += node.num_to_left + 1;
+                    right.left = Some(node);
+                    node = right;
+                }
+
+                let right = node.right.take().unwrap();
+                index -= node.num_to_left + 1;
+                size -= node.num_to_left + 1;
+                left_nodes.push(node);
+                node = right;
+            }
+            Ordering::Equal => break,
+        }
+    }
+
+    let mut left = node.left.take();
+    let mut num_to_left = node.num_to_left;
+    while let Some(mut x) = left_nodes.pop() {
+        num_to_left += x.num_to_left + 1;
+        x.right = left;
+        left = Some(x);
+    }
+
+    let mut right = node.right.take();
+    let mut right_size = size - node.num_to_left - 1;
+    while let Some((mut x, x_size)) = right_nodes.pop() {
+        x.num_to_left = right_size;
+        x.left = right;
+        right = Some(x);
+        right_size += x_size;
+    }
+
+    node.left = left;
+    node.right = right;
+    node.num_to_left = num_to_left;
+    node
+}
+
+#[derive(Debug, Default)]
+pub struct SplayTreeList<T> {
+    root: Link<T>,
+    size: usize,
+}
+
+impl<T> SplayTreeList<T> {
+    pub fn new() -> Self {
+        SplayTreeList {
+            root: None,
+            size: 0,
+        }
+    }
+
+    fn splay_at(&mut self, index: usize) -> &mut TreeNode<T> {
+        let root = splay(self.root.take().unwrap(), self.size, index);
+        self.root.get_or_insert(root)
+    }
+
+    // Unlike `TreeList::get`, this takes `&mut self`, since every access splays the tree.
+    pub fn get(&mut self, index: usize) -> Option<&T> {
+        if index >= self.size {
+            None
+        } else {
+            Some(&self.splay_at(index).val)
+        }
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut T> {
+        if index >= self.size {
+            None
+        } else {
+            Some(&mut self.splay_at(index).val)
+        }
+    }
+
+    pub fn push_front(&mut self, val: T) {
+        self.insert(0, val);
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+
+    pub fn push_back(&mut self, val: T) {
+        self.insert(self.size, val);
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(self.size - 1))
+        }
+    }
+
+    pub fn insert(&mut self, index: usize, val: T) {
+        if index > self.size {
+            panic!("Index out of bounds!");
+        } else {
+            let mut node = Box::new(TreeNode::new(val));
+            if index == self.size {
+                if let Some(root) = self.root.take() {
+                    node.left = Some(splay(root, self.size, self.size - 1));
+                    node.num_to_left = self.size;
+                }
+            } else {
+                let mut root = splay(self.root.take().unwrap(), self.size, index);
+                node.left = root.left.take();
+                node.num_to_left = root.num_to_left;
+                root.num_to_left = 0;
+                node.right = Some(root);
+            }
+
+            self.size += 1;
+            self.root = Some(node);
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        if index >= self.size {
+            panic!("Index out of bounds!");
+        } else {
+            let mut root = splay(self.root.take().unwrap(), self.size, index);
+            self.root = match root.left.take() {
+                None => root.right.take(),
+                Some(left) => {
+                    let mut left = splay(left, root.num_to_left, root.num_to_left - 1);
+                    left.right = root.right.take();
+                    Some(left)
+                }
+            };
+
+            self.size -= 1;
+            root.val
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.size = 0;
+        let mut curr = self.root.take();
+        while let Some(mut node) = curr {
+            match node.left.take() {
+                None => {
+                    curr = node.right.take();
+                }
+                Some(mut left) => {
+                    if node.right.is_some() {
+                        node.left = left.right.take();
+                        left.right = Some(node);
+                        curr = Some(left);
+                    } else {
+                        curr = Some(left);
+                    }
+                }
+            }
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut curr = &self.root;
+        let mut stack: Vec<&TreeNode<T>> = Vec::new();
+        while let Some(ref node) = curr {
+            stack.push(node);
+            curr = &node.left;
+        }
+
+        Iter { stack }
+    }
+}
+
+impl<T> Drop for SplayTreeList<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+pub struct Iter<'a, T> {
+    stack: Vec<&'a TreeNode<T>>,
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let next = self.stack.pop();
+
+        next.map(|node| {
+            let mut curr = &node.right;
+            while let Some(ref x) = curr {
+                self.stack.push(x);
+                curr = &x.left;
+            }
+            &node.val
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Link, SplayTreeList};
+    use crate::conformance::{self, impl_test_list};
+    use std::cmp::Ordering;
+
+    // Finds the element at `index` without splaying.
+    fn peek<T>(root: &Link<T>, mut index: usize) -> &T {
+        let mut node = root.as_ref().unwrap();
+        loop {
+            match index.cmp(&node.num_to_left) {
+                Ordering::Less => node = node.left.as_ref().unwrap(),
+                Ordering::Greater => {
+                    index -= node.num_to_left + 1;
+                    node = node.right.as_ref().unwrap();
+                }
+                Ordering::Equal => break &node.val,
+            }
+        }
+    }
+
+    // Asserts that every index reaches the element at that position in an in-order traversal,
+    // which only holds if every node's `num_to_left` is accurate.
+    fn check<T: PartialEq + std::fmt::Debug>(tree: &SplayTreeList<T>) {
+        assert_eq!(tree.iter().count(), tree.len());
+        for (i, val) in tree.iter().enumerate() {
+            assert_eq!(peek(&tree.root, i), val);
+        }
+    }
+
+    impl_test_list!(SplayTreeList<usize>, SplayTreeList::new(), check);
+
+    #[test]
+    fn test_conformance() {
+        conformance::run::<SplayTreeList<usize>>();
+    }
+
+    #[test]
+    fn test_get_splays_to_root() {
+        let mut tree: SplayTreeList<usize> = SplayTreeList::new();
+        for i in 0..100 {
+            tree.push_back(i);
+        }
+
+        for &i in [50, 0, 99, 51, 49, 50].iter() {
+            assert_eq!(tree.get(i), Some(&i));
+            assert_eq!(tree.root.as_ref().unwrap().num_to_left, i);
+            check(&tree);
+        }
+
+        *tree.get_mut(10).unwrap() = 1000;
+        assert_eq!(tree.get(10), Some(&1000));
+        assert_eq!(tree.get(100), None);
+    }
+
+    #[test]
+    fn test_deep_tree() {
+        let mut tree: SplayTreeList<usize> = SplayTreeList::new();
+        for i in 0..100_000 {
+            tree.push_back(i);
+        }
+
+        assert_eq!(tree.get(0), Some(&0));
+        assert_eq!(tree.get(99_999), Some(&99_999));
+        assert_eq!(tree.remove(50_000), 50_000);
+        assert_eq!(tree.get(50_000), Some(&50_001));
+        assert_eq!(tree.len(), 99_999);
+    }
+}
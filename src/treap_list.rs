@@ -0,0 +1,546 @@
+use crate::tree_list::resolve_range;
+use std::cmp::Ordering;
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+use std::ops::RangeBounds;
+type Link<T> = Option<Box<TreeNode<T>>>;
+
+// A set `reversed` flag means that both subtrees still have to be reversed. The node itself is
+// always up to date, so `num_to_left` counts its current left subtree, but anything reading
+// further down has to account for the flags above it.
+#[derive(Debug)]
+struct TreeNode<T> {
+    val: T,
+    num_to_left: usize,
+    priority: u64,
+    reversed: bool,
+    left: Link<T>,
+    right: Link<T>,
+}
+
+impl<T> TreeNode<T> {
+    fn new(val: T, priority: u64) -> Self {
+        TreeNode {
+            val,
+            num_to_left: 0,
+            priority,
+            reversed: false,
+            left: None,
+            right: None,
+        }
+    }
+
+    // Reverses this subtree, which holds `size` elements, deferring the work below the root.
+    fn reverse(&mut self, size: usize) {
+        std::mem::swap(&mut self.left, &mut self.right);
+        self.num_to_left = size - self.num_to_left - 1;
+        self.reversed = !self.reversed;
+    }
+
+    // Carries out a pending reversal one level further down.
+    fn push_down(&mut self, size: usize) {
+        if self.reversed {
+            self.reversed = false;
+            let right_size = size - self.num_to_left - 1;
+            if let Some(left) = &mut self.left {
+                left.reverse(self.num_to_left);
+            }
+            if let Some(right) = &mut self.right {
+                right.reverse(right_size);
+            }
+        }
+    }
+
+    // The children of this node as seen through an odd or even number of pending reversals
+    // above it, along with whether the children themselves are seen reversed.
+    fn children(&self, flipped: bool) -> (&Link<T>, &Link<T>, bool) {
+        if flipped {
+            (&self.right, &self.left, !self.reversed)
+        } else {
+            (&self.left, &self.right, self.reversed)
+        }
+    }
+}
+
+// A xorshift64* generator; plenty for drawing heap priorities, and cheap to seed.
+#[derive(Debug, Clone)]
+struct Rng(u64);
+
+impl Rng {
+    fn new(seed: u64) -> Self {
+        // The state must never be zero.
+        Rng(seed ^ 0x9e37_79b9_7f4a_7c15 | 1)
+    }
+
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 >> 12;
+        self.0 ^= self.0 << 25;
+        self.0 ^= self.0 >> 27;
+        self.0.wrapping_mul(0x2545_f491_4f6c_dd1d)
+    }
+}
+
+#[derive(Debug)]
+pub struct TreapList<T> {
+    root: Link<T>,
+    size: usize,
+    rng: Rng,
+}
+
+impl<T> Default for TreapList<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> TreapList<T> {
+    pub fn new() -> Self {
+        Self::with_seed(RandomState::new().build_hasher().finish())
+    }
+
+    pub fn with_seed(seed: u64) -> Self {
+        TreapList {
+            root: None,
+            size: 0,
+            rng: Rng::new(seed),
+        }
+    }
+
+    fn split_aux(node: Link<T>, size: usize, index: usize) -> (Link<T>, Link<T>) {
+        match node {
+            None => (None, None),
+            Some(mut x) => {
+                x.push_down(size);
+                if index <= x.num_to_left {
+                    let (left, right) = Self::split_aux(x.left.take(), x.num_to_left, index);
+                    x.left = right;
+                    x.num_to_left -= index;
+                    (left, Some(x))
+                } else {
+                    let right_size = size - x.num_to_left - 1;
+                    let index = index - x.num_to_left - 1;
+                    let (left, right) = Self::split_aux(x.right.take(), right_size, index);
+                    x.right = left;
+                    (Some(x), right)
+                }
+            }
+        }
+    }
+
+    fn merge_aux(left: Link<T>, left_size: usize, right: Link<T>, right_size: usize) -> Link<T> {
+        match (left, right) {
+            (None, right) => right,
+            (left, None) => left,
+            (Some(mut l), Some(mut r)) => {
+                if l.priority > r.priority {
+                    l.push_down(left_size);
+                    let l_right_size = left_size - l.num_to_left - 1;
+                    l.right = Self::merge_aux(l.right.take(), l_right_size, Some(r), right_size);
+                    Some(l)
+                } else {
+                    r.push_down(right_size);
+                    let r_left_size = r.num_to_left;
+                    r.num_to_left += left_size;
+                    r.left = Self::merge_aux(Some(l), left_size, r.left.take(), r_left_size);
+                    Some(r)
+                }
+            }
+        }
+    }
+
+    pub fn split(mut self, index: usize) -> (TreapList<T>, TreapList<T>) {
+        let right = self.split_off(index);
+        (self, right)
+    }
+
+    pub fn merge(mut left: TreapList<T>, mut right: TreapList<T>) -> TreapList<T> {
+        left.append(&mut right);
+        left
+    }
+
+    pub fn split_off(&mut self, at: usize) -> TreapList<T> {
+        if at > self.size {
+            panic!("Index out of bounds!");
+        } else {
+            let (left, right) = Self::split_aux(self.root.take(), self.size, at);
+            let other = TreapList {
+                root: right,
+                size: self.size - at,
+                rng: Rng::new(self.rng.next()),
+            };
+
+            self.root = left;
+            self.size = at;
+            other
+        }
+    }
+
+    pub fn append(&mut self, other: &mut TreapList<T>) {
+        let right = other.root.take();
+        self.root = Self::merge_aux(self.root.take(), self.size, right, other.size);
+        self.size += other.size;
+        other.size = 0;
+    }
+
+    pub fn get(&self, mut index: usize) -> Option<&T> {
+        if index >= self.size {
+            None
+        } else {
+            let mut node = self.root.as_ref().unwrap();
+            let mut size = self.size;
+            let mut flipped = false;
+
+            loop {
+                let num_to_left = if flipped {
+                    size - node.num_to_left - 1
+                } else {
+                    node.num_to_left
+                };
+                let (left, right, children_flipped) = node.children(flipped);
+                flipped = children_flipped;
+
+                match index.cmp(&num_to_left) {
+                    Ordering::Less => {
+                        node = left.as_ref().unwrap();
+                        size = num_to_left;
+                    }
+                    Ordering::Greater => {
+                        index -= num_to_left + 1;
+                        node = right.as_ref().unwrap();
+                        size -= num_to_left + 1;
+                    }
+                    Ordering::Equal => break Some(&node.val),
+                }
+            }
+        }
+    }
+
+    // Unlike `get`, this can afford to push pending reversals down along the way.
+    pub fn get_mut(&mut self, mut index: usize) -> Option<&mut T> {
+        if index >= self.size {
+            None
+        } else {
+            let mut node = self.root.as_mut().unwrap();
+            let mut size = self.size;
+
+            loop {
+                node.push_down(size);
+                match index.cmp(&node.num_to_left) {
+                    Ordering::Less => {
+                        size = node.num_to_left;
+                        node = node.left.as_mut().unwrap();
+                    }
+                    Ordering::Greater => {
+                        index -= node.num_to_left + 1;
+                        size -= node.num_to_left + 1;
+                        node = node.right.as_mut().unwrap();
+                    }
+                    Ordering::Equal => break Some(&mut node.val),
+                }
+            }
+        }
+    }
+
+    pub fn push_front(&mut self, val: T) {
+        self.insert(0, val);
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+
+    pub fn push_back(&mut self, val: T) {
+        self.insert(self.size, val);
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(self.size - 1))
+        }
+    }
+
+    pub fn insert(&mut self, index: usize, val: T) {
+        if index > self.size {
+            panic!("Index out of bounds!");
+        } else {
+            let node = Some(Box::new(TreeNode::new(val, self.rng.next())));
+            let (left, right) = Self::split_aux(self.root.take(), self.size, index);
+            let left = Self::merge_aux(left, index, node, 1);
+            self.root = Self::merge_aux(left, index + 1, right, self.size - index);
+            self.size += 1;
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        if index >= self.size {
+            panic!("Index out of bounds!");
+        } else {
+            let (left, right) = Self::split_aux(self.root.take(), self.size, index);
+            let (mid, right) = Self::split_aux(right, self.size - index, 1);
+            self.root = Self::merge_aux(left, index, right, self.size - index - 1);
+            self.size -= 1;
+            mid.unwrap().val
+        }
+    }
+
+    // Reverses the elements in `range` in `O(log n)` time, by cutting the range out as a
+    // subtree and flagging it rather than moving any elements.
+    pub fn reverse_range<R: RangeBounds<usize>>(&mut self, range: R) {
+        let (start, end) = resolve_range(range, self.size);
+        let (left, right) = Self::split_aux(self.root.take(), self.size, start);
+        let (mut mid, right) = Self::split_aux(right, self.size - start, end - start);
+        if let Some(mid) = &mut mid {
+            mid.reverse(end - start);
+        }
+
+        let left = Self::merge_aux(left, start, mid, end - start);
+        self.root = Self::merge_aux(left, end, right, self.size - end);
+    }
+
+    pub fn clear(&mut self) {
+        self.size = 0;
+        self.root = None;
+    }
+
+    pub fn len(&self) -> usize {
+        self.size
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.size == 0
+    }
+
+    pub fn iter(&self) -> Iter<'_, T> {
+        let mut iter = Iter { stack: Vec::new() };
+        iter.push_left(&self.root, false);
+        iter
+    }
+}
+
+impl<T> Drop for TreapList<T> {
+    fn drop(&mut self) {
+        self.clear();
+    }
+}
+
+// Each stack entry remembers whether its node is seen through pending reversals, in which case
+// its subtrees are walked in the opposite order.
+pub struct Iter<'a, T> {
+    stack: Vec<(&'a TreeNode<T>, bool)>,
+}
+
+impl<'a, T> Iter<'a, T> {
+    fn push_left(&mut self, mut curr: &'a Link<T>, mut flipped: bool) {
+        while let Some(ref node) = curr {
+            self.stack.push((node, flipped));
+            let (left, _, children_flipped) = node.children(flipped);
+            curr = left;
+            flipped = children_flipped;
+        }
+    }
+}
+
+impl<'a, T> Iterator for Iter<'a, T> {
+    type Item = &'a T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let (node, flipped) = self.stack.pop()?;
+        let (_, right, children_flipped) = node.children(flipped);
+        self.push_left(right, children_flipped);
+        Some(&node.val)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Link, TreapList};
+    use crate::conformance::{self, impl_test_list};
+
+    // Returns the size of the subtree at `link`, asserting that every node's `num_to_left` is
+    // accurate and that priorities form a max-heap.
+    fn check_node<T>(link: &Link<T>) -> usize {
+        match link {
+            None => 0,
+            Some(node) => {
+                for child in node.left.iter().chain(node.right.iter()) {
+                    assert!(child.priority <= node.priority);
+                }
+
+                let left = check_node(&node.left);
+                assert_eq!(node.num_to_left, left);
+                left + check_node(&node.right) + 1
+            }
+        }
+    }
+
+    fn check<T>(tree: &TreapList<T>) {
+        assert_eq!(check_node(&tree.root), tree.len());
+    }
+
+    fn depth<T>(link: &Link<T>) -> usize {
+        link.as_ref().map_or(0, |node| {
+            1 + std::cmp::max(depth(&node.left), depth(&node.right))
+        })
+    }
+
+    impl_test_list!(TreapList<usize>, TreapList::with_seed(42), check);
+
+    #[test]
+    fn test_conformance() {
+        conformance::run::<TreapList<usize>>();
+    }
+
+    #[test]
+    fn test_push_back_balanced() {
+        let mut tree: TreapList<usize> = TreapList::with_seed(7);
+
+        for i in 0..1000 {
+            tree.push_back(i);
+        }
+
+        check(&tree);
+        assert!(depth(&tree.root) <= 40);
+        assert_eq!(tree.get(500), Some(&500));
+    }
+
+    #[test]
+    fn test_seed_reproducible() {
+        let mut a: TreapList<usize> = TreapList::with_seed(3);
+        let mut b: TreapList<usize> = TreapList::with_seed(3);
+
+        for i in 0..100 {
+            a.push_back(i);
+            b.push_back(i);
+        }
+
+        assert_eq!(depth(&a.root), depth(&b.root));
+        assert_eq!(
+            a.root.as_ref().map(|node| node.num_to_left),
+            b.root.as_ref().map(|node| node.num_to_left)
+        );
+    }
+
+    #[test]
+    fn test_split_merge() {
+        let mut tree: TreapList<usize> = TreapList::with_seed(1);
+        for i in 0..100 {
+            tree.push_back(i);
+        }
+
+        let (left, right) = tree.split(30);
+        check(&left);
+        check(&right);
+        assert_eq!(
+            left.iter().copied().collect::<Vec<usize>>(),
+            (0..30).collect::<Vec<usize>>()
+        );
+        assert_eq!(
+            right.iter().copied().collect::<Vec<usize>>(),
+            (30..100).collect::<Vec<usize>>()
+        );
+
+        let tree = TreapList::merge(right, left);
+        check(&tree);
+        assert_eq!(tree.len(), 100);
+        assert_eq!(tree.get(69), Some(&99));
+        assert_eq!(tree.get(70), Some(&0));
+    }
+
+    #[test]
+    fn test_split_off_append() {
+        let mut tree: TreapList<char> = TreapList::with_seed(0);
+        tree.push_back('a');
+        tree.push_back('b');
+
+        let mut rest = tree.split_off(2);
+        assert!(rest.is_empty());
+        rest.push_back('c');
+
+        let mut front = tree.split_off(0);
+        assert!(tree.is_empty());
+        front.append(&mut rest);
+
+        check(&front);
+        assert!(rest.is_empty());
+        assert_eq!(
+            front.iter().copied().collect::<Vec<char>>(),
+            ['a', 'b', 'c'].to_vec()
+        );
+    }
+
+    #[test]
+    fn test_reverse_range() {
+        let mut tree: TreapList<char> = TreapList::with_seed(0);
+        for c in "abcdef".chars() {
+            tree.push_back(c);
+        }
+
+        tree.reverse_range(1..4);
+        check(&tree);
+        assert_eq!(tree.iter().collect::<String>(), "adcbef");
+
+        tree.reverse_range(..);
+        assert_eq!(tree.iter().collect::<String>(), "febcda");
+        tree.reverse_range(2..2);
+        tree.reverse_range(5..=5);
+        assert_eq!(tree.iter().collect::<String>(), "febcda");
+        assert_eq!(tree.get(1), Some(&'e'));
+
+        *tree.get_mut(4).unwrap() = 'x';
+        tree.reverse_range(3..);
+        check(&tree);
+        assert_eq!(tree.iter().collect::<String>(), "febaxc");
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of bounds!")]
+    fn test_reverse_range_out_of_bounds() {
+        let mut tree: TreapList<usize> = TreapList::with_seed(0);
+        tree.push_back(0);
+        tree.reverse_range(0..2);
+    }
+
+    #[test]
+    fn test_reverse_range_random() {
+        let mut tree: TreapList<usize> = TreapList::with_seed(5);
+        let mut vec: Vec<usize> = Vec::new();
+
+        for i in 0..500 {
+            let index = (i * 7919) % (vec.len() + 1);
+            tree.insert(index, i);
+            vec.insert(index, i);
+
+            let start = (i * 104_729) % vec.len();
+            let end = start + (i * 31) % (vec.len() - start + 1);
+            tree.reverse_range(start..end);
+            vec[start..end].reverse();
+
+            if i % 3 == 0 {
+                let index = (i * 13) % vec.len();
+                assert_eq!(tree.remove(index), vec.remove(index));
+            }
+        }
+
+        check(&tree);
+        assert_eq!(tree.iter().copied().collect::<Vec<usize>>(), vec);
+        for (i, val) in vec.iter().enumerate() {
+            assert_eq!(tree.get(i), Some(val));
+        }
+
+        let right = tree.split_off(100);
+        let mut tree = TreapList::merge(right, tree);
+        vec.rotate_left(100);
+        for i in (0..vec.len()).step_by(7) {
+            *tree.get_mut(i).unwrap() += 1000;
+            vec[i] += 1000;
+        }
+        check(&tree);
+        assert_eq!(tree.iter().copied().collect::<Vec<usize>>(), vec);
+    }
+}
@@ -1,41 +1,278 @@
+use crate::balance::{Avl, BalancePolicy};
+use crate::monoid::Monoid;
+pub use crate::node::{IntoIter, Iter, IterMut};
+use crate::node::{Link, TreeNode};
 use std::cmp::Ordering;
-type Link<T> = Option<Box<TreeNode<T>>>;
+use std::error::Error;
+use std::fmt;
+use std::hash::{Hash, Hasher};
+use std::iter::{FromIterator, FusedIterator};
+use std::marker::PhantomData;
+use std::ops::{Bound, Index, IndexMut, RangeBounds};
+
+// These work on trees of any monoid, so each one pushes down whatever the monoid has pending on
+// a node before looking below it.
+fn update<T, P, M, F>(link: &mut Link<T, P, M>, f: F)
+where
+    P: BalancePolicy,
+    M: Monoid<T>,
+    F: FnOnce(Box<TreeNode<T, P, M>>) -> Box<TreeNode<T, P, M>>,
+{
+    if let Some(node) = link.take() {
+        *link = Some(f(node));
+    }
+}
 
-#[derive(Debug)]
-struct TreeNode<T> {
+// Insertion and removal detach the nodes along their path as they descend, and reattach them
+// on the way back up, so they don't recurse, however deep an `Unbalanced` tree gets.
+fn insert_at<T, P: BalancePolicy, M: Monoid<T>>(
+    link: &mut Link<T, P, M>,
+    mut index: usize,
     val: T,
-    num_to_left: usize,
-    left: Link<T>,
-    right: Link<T>,
+) {
+    let mut path = Vec::new();
+    let mut curr = link.take();
+    while let Some(mut node) = curr {
+        M::push_down(&mut node);
+        if index <= node.num_to_left {
+            node.num_to_left += 1;
+            curr = node.left.take();
+            path.push((node, true));
+        } else {
+            index -= node.num_to_left + 1;
+            curr = node.right.take();
+            path.push((node, false));
+        }
+    }
+
+    *link = Some(Box::new(TreeNode::new(val)));
+    reattach(link, path);
+}
+
+fn remove_at<T, P: BalancePolicy, M: Monoid<T>>(link: &mut Link<T, P, M>, mut index: usize) -> T {
+    let mut path = Vec::new();
+    // The nodes in `path` whose values are replaced by their successors', each of which is
+    // removed in turn further down.
+    let mut replaced = Vec::new();
+    let mut curr = link.take().unwrap();
+    let mut res = loop {
+        M::push_down(&mut curr);
+        if index < curr.num_to_left {
+            let mut node = P::descend_left(curr);
+            node.num_to_left -= 1;
+            curr = node.left.take().unwrap();
+            path.push((node, true));
+        } else {
+            let mut node = P::descend_right(curr);
+            if index > node.num_to_left {
+                index -= node.num_to_left + 1;
+            } else if node.right.is_some() {
+                replaced.push(path.len());
+                index = 0;
+            } else {
+                let node = *node;
+                *link = node.left;
+                break node.val;
+            }
+            curr = node.right.take().unwrap();
+            path.push((node, false));
+        }
+    };
+
+    for i in replaced.into_iter().rev() {
+        res = std::mem::replace(&mut path[i].0.val, res);
+    }
+    reattach(link, path);
+    res
+}
+
+type Path<T, P, M> = Vec<(Box<TreeNode<T, P, M>>, bool)>;
+
+// Hangs `link` back under the detached nodes of `path`, each of which went left or right, and
+// rebalances them from the bottom up.
+fn reattach<T, P: BalancePolicy, M: Monoid<T>>(link: &mut Link<T, P, M>, mut path: Path<T, P, M>) {
+    while let Some((mut node, went_left)) = path.pop() {
+        if went_left {
+            node.left = link.take();
+        } else {
+            node.right = link.take();
+        }
+        *link = Some(P::rebalance(node));
+    }
 }
 
-impl<T> TreeNode<T> {
-    fn new(val: T) -> Self {
-        TreeNode {
-            val,
-            num_to_left: 0,
-            left: None,
-            right: None,
+// Splits the subtree `link` into its first `index` elements and the rest.
+fn split_at<T, P: BalancePolicy, M: Monoid<T>>(
+    link: Link<T, P, M>,
+    index: usize,
+) -> (Link<T, P, M>, Link<T, P, M>) {
+    match link {
+        None => (None, None),
+        Some(mut node) => {
+            M::push_down(&mut node);
+            let left = node.left.take();
+            let right = node.right.take();
+            let num_to_left = node.num_to_left;
+
+            if index <= num_to_left {
+                let (left, mid) = split_at(left, index);
+                let right = P::join(mid, num_to_left - index, node, right);
+                (left, Some(right))
+            } else {
+                let (mid, right) = split_at(right, index - num_to_left - 1);
+                let left = P::join(left, num_to_left, node, mid);
+                (Some(left), right)
+            }
         }
     }
 }
 
-#[derive(Debug, Default)]
-pub struct TreeList<T> {
-    root: Link<T>,
+// The bodies of `insert`, `remove`, `split_off` and `append`, on a tree of `size` elements.
+// `AugmentedTreeList` keeps its tree in the same two fields, so it shares them.
+pub(crate) fn insert_into<T, P: BalancePolicy, M: Monoid<T>>(
+    root: &mut Link<T, P, M>,
+    size: &mut usize,
+    index: usize,
+    val: T,
+) {
+    if index > *size {
+        panic!("Index out of bounds!");
+    } else {
+        *size += 1;
+        insert_at(root, index, val);
+        update(root, P::fix_root);
+    }
+}
+
+pub(crate) fn remove_from<T, P: BalancePolicy, M: Monoid<T>>(
+    root: &mut Link<T, P, M>,
+    size: &mut usize,
+    index: usize,
+) -> T {
+    if index >= *size {
+        panic!("Index out of bounds!");
+    } else {
+        *size -= 1;
+        update(root, P::begin_remove);
+        let res = remove_at(root, index);
+        update(root, P::fix_root);
+        res
+    }
+}
+
+// Leaves the first `at` elements in `root` and returns the tree of the rest.
+pub(crate) fn split_tree<T, P: BalancePolicy, M: Monoid<T>>(
+    root: &mut Link<T, P, M>,
+    size: &mut usize,
+    at: usize,
+) -> Link<T, P, M> {
+    if at > *size {
+        panic!("Index out of bounds!");
+    } else {
+        let (left, mut right) = split_at(root.take(), at);
+        update(&mut right, P::fix_root);
+
+        *root = left;
+        *size = at;
+        update(root, P::fix_root);
+        right
+    }
+}
+
+// Moves every element of `other` onto the end of `root`, leaving `other` empty.
+pub(crate) fn append_tree<T, P: BalancePolicy, M: Monoid<T>>(
+    root: &mut Link<T, P, M>,
+    size: &mut usize,
+    other: &mut Link<T, P, M>,
+    other_size: &mut usize,
+) {
+    if *other_size > 0 {
+        let val = remove_from(other, other_size, 0);
+        let mid = Box::new(TreeNode::new(val));
+        let joined = P::join(root.take(), *size, mid, other.take());
+        *root = Some(P::fix_root(joined));
+        *size += std::mem::replace(other_size, 0) + 1;
+    }
+}
+
+// Resolves `range` against a length of `len`, as `Vec` does, panicking if it doesn't fit.
+pub(crate) fn resolve_range<R: RangeBounds<usize>>(range: R, len: usize) -> (usize, usize) {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end + 1,
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+
+    if start > end || end > len {
+        panic!("Index out of bounds!");
+    }
+    (start, end)
+}
+
+// Returned by the `try_*` methods when an index is out of bounds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TreeListError {
+    pub index: usize,
+    pub len: usize,
+}
+
+impl fmt::Display for TreeListError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "index {} out of bounds for list of length {}",
+            self.index, self.len
+        )
+    }
+}
+
+impl Error for TreeListError {}
+
+pub struct TreeList<T, P: BalancePolicy = Avl> {
+    pub(crate) root: Link<T, P>,
     size: usize,
 }
 
 impl<T> TreeList<T> {
     pub fn new() -> Self {
+        Self::with_policy()
+    }
+}
+
+impl<T, P: BalancePolicy> Default for TreeList<T, P> {
+    fn default() -> Self {
+        Self::with_policy()
+    }
+}
+
+impl<T, P: BalancePolicy> TreeList<T, P> {
+    pub fn with_policy() -> Self {
         TreeList {
             root: None,
             size: 0,
         }
     }
 
+    // Builds a balanced tree directly, in linear time, rather than inserting one by one.
+    pub fn from_vec(vec: Vec<T>) -> Self {
+        Self::from_exact_iter(vec.into_iter())
+    }
+
+    fn from_exact_iter<I: ExactSizeIterator<Item = T>>(mut iter: I) -> Self {
+        let size = iter.len();
+        TreeList {
+            root: P::build(&mut iter, size),
+            size,
+        }
+    }
+
     pub fn get(&self, mut index: usize) -> Option<&T> {
-        if index > self.size {
+        if index >= self.size {
             None
         } else {
             let mut node = self.root.as_ref().unwrap();
@@ -54,7 +291,7 @@ impl<T> TreeList<T> {
     }
 
     pub fn get_mut(&mut self, mut index: usize) -> Option<&mut T> {
-        if index > self.size {
+        if index >= self.size {
             None
         } else {
             let mut node = self.root.as_mut().unwrap();
@@ -72,152 +309,185 @@ impl<T> TreeList<T> {
         }
     }
 
-    pub fn push_front(&mut self, val: T) {
-        self.size += 1;
+    // These assume the list is sorted, as the slice methods of the same names do. Each one
+    // descends the tree once, so it costs no more than a single `get`.
+    pub fn binary_search(&self, x: &T) -> Result<usize, usize>
+    where
+        T: Ord,
+    {
+        self.binary_search_by(|val| val.cmp(x))
+    }
 
-        let mut curr = &mut self.root;
-        while let Some(node) = curr {
-            node.num_to_left += 1;
-            curr = &mut node.left;
-        }
+    pub fn binary_search_by<F: FnMut(&T) -> Ordering>(&self, f: F) -> Result<usize, usize> {
+        TreeNode::binary_search_by(&self.root, f)
+    }
 
-        *curr = Some(Box::new(TreeNode::new(val)));
+    pub fn binary_search_by_key<B, F>(&self, b: &B, mut f: F) -> Result<usize, usize>
+    where
+        B: Ord,
+        F: FnMut(&T) -> B,
+    {
+        self.binary_search_by(|val| f(val).cmp(b))
     }
 
-    pub fn pop_front(&mut self) -> Option<T> {
-        match &mut self.root {
-            None => None,
-            curr => {
-                self.size -= 1;
-                let mut curr = curr;
-
-                while curr.as_mut().unwrap().left.is_some() {
-                    curr.as_mut().unwrap().num_to_left -= 1;
-                    curr = &mut curr.as_mut().unwrap().left;
-                }
+    pub fn partition_point<F: FnMut(&T) -> bool>(&self, pred: F) -> usize {
+        TreeNode::partition_point(&self.root, pred)
+    }
 
-                let mut tmp = curr.take().unwrap();
-                *curr = tmp.right.take();
-                Some(tmp.val)
-            }
+    pub fn push_front(&mut self, val: T) {
+        self.insert(0, val);
+    }
+
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(0))
         }
     }
 
     pub fn push_back(&mut self, val: T) {
-        self.size += 1;
+        self.insert(self.size, val);
+    }
 
-        let mut curr = &mut self.root;
-        while let Some(node) = curr {
-            curr = &mut node.right;
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(self.size - 1))
         }
+    }
 
-        *curr = Some(Box::new(TreeNode::new(val)));
+    pub fn insert(&mut self, index: usize, val: T) {
+        insert_into(&mut self.root, &mut self.size, index, val);
     }
 
-    pub fn pop_back(&mut self) -> Option<T> {
-        match &mut self.root {
-            None => None,
-            curr => {
-                self.size -= 1;
-                let mut curr = curr;
-
-                while curr.as_mut().unwrap().right.is_some() {
-                    curr = &mut curr.as_mut().unwrap().right;
-                }
+    pub fn remove(&mut self, index: usize) -> T {
+        remove_from(&mut self.root, &mut self.size, index)
+    }
 
-                let mut tmp = curr.take().unwrap();
-                *curr = tmp.left.take();
-                Some(tmp.val)
-            }
+    pub fn try_insert(&mut self, index: usize, val: T) -> Result<(), TreeListError> {
+        if index > self.size {
+            Err(TreeListError {
+                index,
+                len: self.size,
+            })
+        } else {
+            self.insert(index, val);
+            Ok(())
         }
     }
 
-    pub fn insert(&mut self, index: usize, val: T) {
-        if index > self.size {
-            panic!("Index out of bounds!");
+    pub fn try_remove(&mut self, index: usize) -> Result<T, TreeListError> {
+        if index >= self.size {
+            Err(TreeListError {
+                index,
+                len: self.size,
+            })
         } else {
-            self.size += 1;
-
-            let mut index = index;
-            let mut curr = &mut self.root;
-            while let Some(node) = curr {
-                if index <= node.num_to_left {
-                    node.num_to_left += 1;
-                    curr = &mut node.left;
-                } else {
-                    index -= node.num_to_left + 1;
-                    curr = &mut node.right;
-                }
+            Ok(self.remove(index))
+        }
+    }
+
+    pub fn swap(&mut self, a: usize, b: usize) {
+        if let Err(err) = self.try_swap(a, b) {
+            panic!("{}", err);
+        }
+    }
+
+    // Swaps values in place, so unlike a remove and reinsert it never restructures the tree.
+    pub fn try_swap(&mut self, a: usize, b: usize) -> Result<(), TreeListError> {
+        for &index in [a, b].iter() {
+            if index >= self.size {
+                return Err(TreeListError {
+                    index,
+                    len: self.size,
+                });
             }
+        }
 
-            *curr = Some(Box::new(TreeNode::new(val)));
+        if a != b {
+            let root = self.root.as_mut().unwrap();
+            TreeNode::swap(root, std::cmp::min(a, b), std::cmp::max(a, b));
         }
+        Ok(())
     }
 
-    pub fn remove(&mut self, mut index: usize) -> T {
-        if index >= self.size {
+    pub fn split_off(&mut self, at: usize) -> TreeList<T, P> {
+        let size = self.size;
+        let root = split_tree(&mut self.root, &mut self.size, at);
+        TreeList {
+            root,
+            size: size - at,
+        }
+    }
+
+    pub fn append(&mut self, other: &mut TreeList<T, P>) {
+        append_tree(
+            &mut self.root,
+            &mut self.size,
+            &mut other.root,
+            &mut other.size,
+        );
+    }
+
+    pub fn insert_list(&mut self, index: usize, mut other: TreeList<T, P>) {
+        let mut tail = self.split_off(index);
+        self.append(&mut other);
+        self.append(&mut tail);
+    }
+
+    // A single split and append, rather than moving elements one at a time.
+    pub fn rotate_left(&mut self, k: usize) {
+        let mut tail = self.split_off(k);
+        std::mem::swap(self, &mut tail);
+        self.append(&mut tail);
+    }
+
+    pub fn rotate_right(&mut self, k: usize) {
+        if k > self.size {
             panic!("Index out of bounds!");
         } else {
-            self.size -= 1;
-
-            let mut curr = &mut self.root;
-            loop {
-                let num_to_left = curr.as_mut().unwrap().num_to_left;
-                match index.cmp(&num_to_left) {
-                    Ordering::Less => {
-                        curr.as_mut().unwrap().num_to_left -= 1;
-                        curr = &mut curr.as_mut().unwrap().left;
-                    }
-                    Ordering::Greater => {
-                        index -= num_to_left + 1;
-                        curr = &mut curr.as_mut().unwrap().right;
-                    }
-                    Ordering::Equal => break,
-                }
-            }
+            self.rotate_left(self.size - k);
+        }
+    }
 
-            if curr.as_mut().unwrap().left.is_none() {
-                let mut tmp = curr.take().unwrap();
-                *curr = tmp.right.take();
-                tmp.val
-            } else if curr.as_mut().unwrap().right.is_none() {
-                let mut tmp = curr.take().unwrap();
-                *curr = tmp.left.take();
-                tmp.val
-            } else {
-                let curr = curr.as_mut().unwrap();
-                let mut to_delete = &mut curr.right;
-                while to_delete.as_mut().unwrap().left.is_some() {
-                    to_delete.as_mut().unwrap().num_to_left -= 1;
-                    to_delete = &mut to_delete.as_mut().unwrap().left;
-                }
+    // Cuts out the elements in `start..end`, joining the pieces on either side back together.
+    fn split_range(&mut self, start: usize, end: usize) -> TreeList<T, P> {
+        let mut tail = self.split_off(end);
+        let range = self.split_off(start);
+        self.append(&mut tail);
+        range
+    }
 
-                let mut tmp = to_delete.take();
-                *to_delete = tmp.as_mut().unwrap().right.take();
-                std::mem::replace(&mut curr.val, tmp.unwrap().val)
-            }
+    // The range is cut out up front, so the elements are gone from the list even if the
+    // returned iterator is leaked.
+    pub fn drain<R: RangeBounds<usize>>(&mut self, range: R) -> Drain<'_, T, P> {
+        let (start, end) = resolve_range(range, self.size);
+        let mut range = self.split_range(start, end);
+        let len = std::mem::replace(&mut range.size, 0);
+        Drain {
+            iter: IntoIter::new(range.root.take(), len),
+            marker: PhantomData,
         }
     }
 
+    pub fn splice<R, I>(&mut self, range: R, replace_with: I) -> Drain<'_, T, P>
+    where
+        R: RangeBounds<usize>,
+        I: IntoIterator<Item = T>,
+    {
+        let (start, end) = resolve_range(range, self.size);
+        let replacement = replace_with.into_iter().collect();
+
+        // Slot the replacement in after the range, then drain the range itself.
+        self.insert_list(end, replacement);
+        self.drain(start..end)
+    }
+
     pub fn clear(&mut self) {
         self.size = 0;
-        let mut curr = self.root.take();
-        while let Some(mut node) = curr {
-            match node.left.take() {
-                None => {
-                    curr = node.right.take();
-                }
-                Some(mut left) => {
-                    if node.right.is_some() {
-                        node.left = left.right.take();
-                        left.right = Some(node);
-                        curr = Some(left);
-                    } else {
-                        curr = Some(left);
-                    }
-                }
-            }
-        }
+        TreeNode::free(self.root.take());
     }
 
     pub fn len(&self) -> usize {
@@ -228,48 +498,427 @@ impl<T> TreeList<T> {
         self.size == 0
     }
 
-    pub fn iter(&self) -> Iter<T> {
-        let mut curr = &self.root;
-        let mut stack: Vec<&TreeNode<T>> = Vec::new();
-        while let Some(ref node) = curr {
-            stack.push(node);
-            curr = &node.left;
+    pub fn iter(&self) -> Iter<'_, T, P> {
+        Iter::new(&self.root, self.size)
+    }
+
+    pub fn iter_mut(&mut self) -> IterMut<'_, T, P> {
+        IterMut::new(&mut self.root, self.size)
+    }
+
+    // Cursors sit either on an element or on a "ghost" position between the back and the front
+    // of the list, as with `LinkedList`. An index equal to the length starts at the ghost.
+    pub fn cursor_at(&self, index: usize) -> Cursor<'_, T, P> {
+        if index > self.size {
+            panic!("Index out of bounds!");
+        } else {
+            let mut cursor = Cursor {
+                root: &self.root,
+                stack: Vec::new(),
+                index,
+                len: self.size,
+            };
+            cursor.seek(index);
+            cursor
+        }
+    }
+
+    pub fn cursor_at_mut(&mut self, index: usize) -> CursorMut<'_, T, P> {
+        if index > self.size {
+            panic!("Index out of bounds!");
+        } else {
+            let rest = self.split_off(index);
+            CursorMut {
+                list: self,
+                before: Vec::new(),
+                after: Vec::new(),
+                rest,
+            }
+        }
+    }
+}
+
+impl<T, P: BalancePolicy> IntoIterator for TreeList<T, P> {
+    type Item = T;
+    type IntoIter = IntoIter<T, P>;
+
+    fn into_iter(mut self) -> Self::IntoIter {
+        let len = std::mem::replace(&mut self.size, 0);
+        IntoIter::new(self.root.take(), len)
+    }
+}
+
+impl<'a, T, P: BalancePolicy> IntoIterator for &'a TreeList<T, P> {
+    type Item = &'a T;
+    type IntoIter = Iter<'a, T, P>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl<'a, T, P: BalancePolicy> IntoIterator for &'a mut TreeList<T, P> {
+    type Item = &'a mut T;
+    type IntoIter = IterMut<'a, T, P>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_mut()
+    }
+}
+
+impl<T, P: BalancePolicy> FromIterator<T> for TreeList<T, P> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self::from_vec(iter.into_iter().collect())
+    }
+}
+
+impl<T, P: BalancePolicy> Extend<T> for TreeList<T, P> {
+    fn extend<I: IntoIterator<Item = T>>(&mut self, iter: I) {
+        self.append(&mut iter.into_iter().collect());
+    }
+}
+
+impl<'a, T: Copy + 'a, P: BalancePolicy> Extend<&'a T> for TreeList<T, P> {
+    fn extend<I: IntoIterator<Item = &'a T>>(&mut self, iter: I) {
+        self.extend(iter.into_iter().copied());
+    }
+}
+
+impl<T, P: BalancePolicy> From<Vec<T>> for TreeList<T, P> {
+    fn from(vec: Vec<T>) -> Self {
+        Self::from_vec(vec)
+    }
+}
+
+impl<T, P: BalancePolicy, const N: usize> From<[T; N]> for TreeList<T, P> {
+    fn from(arr: [T; N]) -> Self {
+        Self::from_exact_iter(IntoIterator::into_iter(arr))
+    }
+}
+
+impl<T, P: BalancePolicy> From<TreeList<T, P>> for Vec<T> {
+    fn from(list: TreeList<T, P>) -> Self {
+        list.into_iter().collect()
+    }
+}
+
+impl<T: Clone, P: BalancePolicy> Clone for TreeList<T, P> {
+    fn clone(&self) -> Self {
+        Self::from_exact_iter(self.iter().cloned())
+    }
+}
+
+impl<T: fmt::Debug, P: BalancePolicy> fmt::Debug for TreeList<T, P> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.iter()).finish()
+    }
+}
+
+// Comparisons and hashing go by the elements alone, so lists that hold the same sequence are
+// equal however their trees happen to be shaped.
+impl<T: PartialEq, P: BalancePolicy> PartialEq for TreeList<T, P> {
+    fn eq(&self, other: &Self) -> bool {
+        self.size == other.size && self.iter().eq(other.iter())
+    }
+}
+
+impl<T: Eq, P: BalancePolicy> Eq for TreeList<T, P> {}
+
+impl<T: PartialOrd, P: BalancePolicy> PartialOrd for TreeList<T, P> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        self.iter().partial_cmp(other.iter())
+    }
+}
+
+impl<T: Ord, P: BalancePolicy> Ord for TreeList<T, P> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.iter().cmp(other.iter())
+    }
+}
+
+impl<T: Hash, P: BalancePolicy> Hash for TreeList<T, P> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.size.hash(state);
+        for val in self {
+            val.hash(state);
         }
+    }
+}
+
+impl<T, P: BalancePolicy> Index<usize> for TreeList<T, P> {
+    type Output = T;
+
+    fn index(&self, index: usize) -> &T {
+        self.get(index).expect("Index out of bounds!")
+    }
+}
 
-        Iter { stack }
+impl<T, P: BalancePolicy> IndexMut<usize> for TreeList<T, P> {
+    fn index_mut(&mut self, index: usize) -> &mut T {
+        self.get_mut(index).expect("Index out of bounds!")
     }
 }
 
-impl<T> Drop for TreeList<T> {
+impl<T, P: BalancePolicy> Drop for TreeList<T, P> {
     fn drop(&mut self) {
         self.clear();
     }
 }
 
-pub struct Iter<'a, T> {
-    stack: Vec<&'a TreeNode<T>>,
+pub struct Drain<'a, T, P: BalancePolicy> {
+    iter: IntoIter<T, P>,
+    marker: PhantomData<&'a mut TreeList<T, P>>,
 }
 
-impl<'a, T> Iterator for Iter<'a, T> {
-    type Item = &'a T;
+impl<'a, T, P: BalancePolicy> Iterator for Drain<'a, T, P> {
+    type Item = T;
 
     fn next(&mut self) -> Option<Self::Item> {
-        let next = self.stack.pop();
+        self.iter.next()
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<'a, T, P: BalancePolicy> DoubleEndedIterator for Drain<'a, T, P> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.iter.next_back()
+    }
+}
 
-        next.map(|node| {
-            let mut curr = &node.right;
-            while let Some(ref x) = curr {
-                self.stack.push(x);
-                curr = &x.left;
+impl<'a, T, P: BalancePolicy> ExactSizeIterator for Drain<'a, T, P> {}
+
+impl<'a, T, P: BalancePolicy> FusedIterator for Drain<'a, T, P> {}
+
+// Keeps the path from the root down to the current element, so stepping to a neighbour only
+// walks the tree between the two, which is O(1) amortized over a sweep.
+pub struct Cursor<'a, T, P: BalancePolicy> {
+    root: &'a Link<T, P>,
+    stack: Vec<&'a TreeNode<T, P>>,
+    index: usize,
+    len: usize,
+}
+
+impl<'a, T, P: BalancePolicy> Cursor<'a, T, P> {
+    // Rebuilds the path for `index`, leaving it empty for the ghost position.
+    fn seek(&mut self, mut index: usize) {
+        self.stack.clear();
+        self.index = index;
+        if index == self.len {
+            return;
+        }
+
+        let mut curr = self.root;
+        while let Some(node) = curr {
+            self.stack.push(node);
+            match index.cmp(&node.num_to_left) {
+                Ordering::Less => curr = &node.left,
+                Ordering::Greater => {
+                    index -= node.num_to_left + 1;
+                    curr = &node.right;
+                }
+                Ordering::Equal => break,
+            }
+        }
+    }
+
+    pub fn index(&self) -> Option<usize> {
+        if self.index == self.len {
+            None
+        } else {
+            Some(self.index)
+        }
+    }
+
+    pub fn current(&self) -> Option<&'a T> {
+        self.stack.last().map(|node| &node.val)
+    }
+
+    pub fn move_next(&mut self) {
+        let node = match self.stack.last() {
+            None => return self.seek(0),
+            Some(node) => *node,
+        };
+
+        self.index += 1;
+        if let Some(ref right) = node.right {
+            let mut curr = right;
+            self.stack.push(curr);
+            while let Some(ref left) = curr.left {
+                self.stack.push(left);
+                curr = left;
             }
-            &node.val
-        })
+        } else {
+            // Climb until we leave a left subtree; running out of ancestors means we were at the
+            // back, and the empty path is the ghost.
+            while let Some(child) = self.stack.pop() {
+                if matches!(self.stack.last(), Some(parent) if is_child(&parent.left, child)) {
+                    break;
+                }
+            }
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        let node = match self.stack.last() {
+            None if self.len == 0 => return,
+            None => return self.seek(self.len - 1),
+            Some(node) => *node,
+        };
+
+        if self.index == 0 {
+            return self.seek(self.len);
+        }
+
+        self.index -= 1;
+        if let Some(ref left) = node.left {
+            let mut curr = left;
+            self.stack.push(curr);
+            while let Some(ref right) = curr.right {
+                self.stack.push(right);
+                curr = right;
+            }
+        } else {
+            while let Some(child) = self.stack.pop() {
+                if matches!(self.stack.last(), Some(parent) if is_child(&parent.right, child)) {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+fn is_child<T, P: BalancePolicy>(link: &Link<T, P>, node: &TreeNode<T, P>) -> bool {
+    matches!(link, Some(child) if std::ptr::eq(&**child, node))
+}
+
+// A mutable cursor splits the list where it stands: `list` keeps the front half and `rest` the
+// back half. The elements on either side of the cursor wait in two stacks between them, `before`
+// in order and `after` reversed, so the current element is the top of `after`. Moving and
+// editing push and pop those stacks, which take about `log n` elements from the halves whenever
+// they run out, paying for the split, so both are O(1) amortized. Dropping the cursor joins the
+// pieces back up.
+pub struct CursorMut<'a, T, P: BalancePolicy> {
+    list: &'a mut TreeList<T, P>,
+    before: Vec<T>,
+    after: Vec<T>,
+    rest: TreeList<T, P>,
+}
+
+// How many elements to take from a half of `len` elements at once.
+fn refill_len(len: usize) -> usize {
+    (usize::BITS - len.leading_zeros()) as usize
+}
+
+impl<'a, T, P: BalancePolicy> CursorMut<'a, T, P> {
+    fn is_ghost(&self) -> bool {
+        self.after.is_empty() && self.rest.is_empty()
+    }
+
+    fn fill_before(&mut self) {
+        if self.before.is_empty() && !self.list.is_empty() {
+            let at = self.list.len() - refill_len(self.list.len());
+            self.before.extend(self.list.split_off(at));
+        }
+    }
+
+    fn fill_after(&mut self) {
+        if self.after.is_empty() && !self.rest.is_empty() {
+            let tail = self.rest.split_off(refill_len(self.rest.len()));
+            let front = std::mem::replace(&mut self.rest, tail);
+            self.after.extend(front.into_iter().rev());
+        }
+    }
+
+    // Moves every element back into `list`, leaving the cursor at the ghost position.
+    fn join(&mut self) {
+        let before = std::mem::take(&mut self.before);
+        self.list.append(&mut TreeList::from_vec(before));
+        let after = std::mem::take(&mut self.after);
+        self.list.append(&mut after.into_iter().rev().collect());
+        self.list.append(&mut self.rest);
+    }
+
+    pub fn index(&self) -> Option<usize> {
+        if self.is_ghost() {
+            None
+        } else {
+            Some(self.list.len() + self.before.len())
+        }
+    }
+
+    pub fn current(&mut self) -> Option<&mut T> {
+        self.fill_after();
+        self.after.last_mut()
+    }
+
+    pub fn move_next(&mut self) {
+        if self.is_ghost() {
+            self.join();
+            std::mem::swap(self.list, &mut self.rest);
+        } else {
+            self.fill_after();
+            self.before.extend(self.after.pop());
+        }
+    }
+
+    pub fn move_prev(&mut self) {
+        if self.list.is_empty() && self.before.is_empty() {
+            self.join();
+        } else {
+            self.fill_before();
+            self.after.extend(self.before.pop());
+        }
+    }
+
+    // At the ghost position, this inserts at the back of the list.
+    pub fn insert_before(&mut self, val: T) {
+        self.before.push(val);
+    }
+
+    // At the ghost position, this inserts at the front of the list.
+    pub fn insert_after(&mut self, val: T) {
+        if self.is_ghost() {
+            self.list.push_front(val);
+        } else {
+            self.fill_after();
+            let current = self.after.pop();
+            self.after.push(val);
+            self.after.extend(current);
+        }
+    }
+
+    // Afterwards the cursor points at the element that followed the removed one.
+    pub fn remove_current(&mut self) -> Option<T> {
+        self.fill_after();
+        self.after.pop()
+    }
+
+    // Does nothing at the ghost position.
+    pub fn replace_current(&mut self, val: T) -> Option<T> {
+        self.current()
+            .map(|current| std::mem::replace(current, val))
+    }
+}
+
+impl<'a, T, P: BalancePolicy> Drop for CursorMut<'a, T, P> {
+    fn drop(&mut self) {
+        self.join();
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use super::TreeList;
+    use super::{TreeList, TreeListError};
+    use crate::balance::tests::{depth, Validate};
+    use crate::balance::{Avl, RedBlack, Unbalanced, WeightBalanced};
+    use crate::node::TreeNode;
+
+    fn check<T, P: Validate>(tree: &TreeList<T, P>) {
+        assert_eq!(P::validate(&tree.root), tree.len());
+    }
 
     #[test]
     fn test_empty_tree() {
@@ -487,4 +1136,667 @@ mod tests {
             ['b', 'd', 'f', 'c', 'e'].to_vec()
         );
     }
+
+    #[test]
+    fn test_edit_deep_tree() {
+        let mut tree: TreeList<usize, Unbalanced> = TreeList::with_policy();
+
+        // Pushing to the front would take quadratic time, so chain the nodes up by hand.
+        for i in 0..1_000_000 {
+            let mut node = Box::new(TreeNode::new(i));
+            node.num_to_left = i;
+            node.left = tree.root.take();
+            tree.root = Some(node);
+            tree.size += 1;
+        }
+
+        tree.push_front(1_000_000);
+        assert_eq!(tree.remove(1), 0);
+        assert_eq!(tree.pop_front(), Some(1_000_000));
+        assert_eq!(tree.get(0), Some(&1));
+        assert_eq!(tree.len(), 999_999);
+    }
+
+    fn push_back_balanced<P: Validate>(max_depth: usize) {
+        let mut tree: TreeList<usize, P> = TreeList::with_policy();
+
+        for i in 0..1000 {
+            tree.push_back(i);
+            check(&tree);
+        }
+
+        assert!(depth(&tree.root) <= max_depth);
+        assert_eq!(tree.get(500), Some(&500));
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<usize>>(),
+            (0..1000).collect::<Vec<usize>>()
+        );
+    }
+
+    #[test]
+    fn test_push_back_balanced() {
+        push_back_balanced::<Avl>(15);
+        push_back_balanced::<WeightBalanced>(20);
+        push_back_balanced::<RedBlack>(20);
+    }
+
+    fn push_front_balanced<P: Validate>(max_depth: usize) {
+        let mut tree: TreeList<usize, P> = TreeList::with_policy();
+
+        for i in 0..1000 {
+            tree.push_front(i);
+        }
+
+        check(&tree);
+        assert!(depth(&tree.root) <= max_depth);
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<usize>>(),
+            (0..1000).rev().collect::<Vec<usize>>()
+        );
+    }
+
+    #[test]
+    fn test_push_front_balanced() {
+        push_front_balanced::<Avl>(15);
+        push_front_balanced::<WeightBalanced>(20);
+        push_front_balanced::<RedBlack>(20);
+    }
+
+    fn insert_remove<P: Validate>() {
+        let mut tree: TreeList<usize, P> = TreeList::with_policy();
+        let mut vec: Vec<usize> = Vec::new();
+
+        for i in 0..1000 {
+            let index = (i * 7919) % (vec.len() + 1);
+            tree.insert(index, i);
+            vec.insert(index, i);
+        }
+        check(&tree);
+
+        for i in 0..600 {
+            let index = (i * 104_729) % vec.len();
+            assert_eq!(tree.remove(index), vec.remove(index));
+            check(&tree);
+        }
+
+        while let Some(val) = tree.pop_front() {
+            assert_eq!(val, vec.remove(0));
+            assert_eq!(tree.pop_back(), vec.pop());
+            check(&tree);
+        }
+        assert!(vec.is_empty());
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    fn test_insert_remove_policies() {
+        insert_remove::<Unbalanced>();
+        insert_remove::<Avl>();
+        insert_remove::<WeightBalanced>();
+        insert_remove::<RedBlack>();
+    }
+
+    fn split_append<P: Validate>() {
+        for &size in [0, 1, 2, 7, 100, 1000].iter() {
+            for &at in [0, 1, size / 3, size / 2, size].iter() {
+                let at = std::cmp::min(at, size);
+                let mut tree: TreeList<usize, P> = TreeList::with_policy();
+                for i in 0..size {
+                    tree.push_back(i);
+                }
+
+                let mut rest = tree.split_off(at);
+                check(&tree);
+                check(&rest);
+                assert_eq!(
+                    rest.iter().copied().collect::<Vec<usize>>(),
+                    (at..size).collect::<Vec<usize>>()
+                );
+
+                // Join in the opposite order too, so that both spines get descended.
+                let mut front = rest.split_off(0);
+                rest.append(&mut tree);
+                check(&rest);
+                assert!(tree.is_empty());
+                tree.append(&mut front);
+                tree.append(&mut rest);
+                check(&tree);
+                assert_eq!(
+                    tree.iter().copied().collect::<Vec<usize>>(),
+                    (at..size).chain(0..at).collect::<Vec<usize>>()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_split_append_policies() {
+        split_append::<Unbalanced>();
+        split_append::<Avl>();
+        split_append::<WeightBalanced>();
+        split_append::<RedBlack>();
+    }
+
+    fn insert_list<P: Validate>() {
+        let mut tree: TreeList<usize, P> = TreeList::with_policy();
+        let mut vec: Vec<usize> = Vec::new();
+
+        for i in 0..50 {
+            let mut other: TreeList<usize, P> = TreeList::with_policy();
+            for j in 0..(i * 37) % 60 {
+                other.push_back(i * 100 + j);
+            }
+
+            let index = (i * 7919) % (vec.len() + 1);
+            let tail = vec.split_off(index);
+            vec.extend(other.iter().copied());
+            vec.extend(tail);
+            tree.insert_list(index, other);
+            check(&tree);
+        }
+
+        assert_eq!(tree.iter().copied().collect::<Vec<usize>>(), vec);
+    }
+
+    #[test]
+    fn test_insert_list_policies() {
+        insert_list::<Unbalanced>();
+        insert_list::<Avl>();
+        insert_list::<WeightBalanced>();
+        insert_list::<RedBlack>();
+    }
+
+    fn rotate<P: Validate>() {
+        let mut tree: TreeList<usize, P> = TreeList::with_policy();
+        let mut vec: Vec<usize> = Vec::new();
+        for i in 0..300 {
+            tree.push_back(i);
+            vec.push(i);
+
+            let k = (i * 7919) % (vec.len() + 1);
+            if i % 2 == 0 {
+                tree.rotate_left(k);
+                vec.rotate_left(k);
+            } else {
+                tree.rotate_right(k);
+                vec.rotate_right(k);
+            }
+            check(&tree);
+        }
+        assert_eq!(tree.iter().copied().collect::<Vec<usize>>(), vec);
+    }
+
+    #[test]
+    fn test_rotate_policies() {
+        rotate::<Unbalanced>();
+        rotate::<Avl>();
+        rotate::<WeightBalanced>();
+        rotate::<RedBlack>();
+    }
+
+    #[test]
+    #[should_panic(expected = "Index out of bounds!")]
+    fn test_rotate_out_of_bounds() {
+        let mut tree: TreeList<usize> = (0..10).collect();
+        tree.rotate_right(11);
+    }
+
+    fn drain_splice<P: Validate>() {
+        let mut tree: TreeList<usize, P> = TreeList::with_policy();
+        let mut vec: Vec<usize> = Vec::new();
+        for i in 0..500 {
+            tree.push_back(i);
+            vec.push(i);
+        }
+
+        for i in 0..40 {
+            let start = (i * 7919) % (vec.len() + 1);
+            let end = start + (i * 31) % (vec.len() - start + 1);
+            if i % 2 == 0 {
+                assert!(tree.drain(start..end).eq(vec.drain(start..end)));
+            } else {
+                let replacement = (0..i).map(|j| 1000 * i + j);
+                assert!(tree
+                    .splice(start..end, replacement.clone())
+                    .eq(vec.splice(start..end, replacement)));
+            }
+            check(&tree);
+        }
+
+        assert_eq!(tree.iter().copied().collect::<Vec<usize>>(), vec);
+    }
+
+    #[test]
+    fn test_drain_splice_policies() {
+        drain_splice::<Unbalanced>();
+        drain_splice::<Avl>();
+        drain_splice::<WeightBalanced>();
+        drain_splice::<RedBlack>();
+    }
+
+    #[test]
+    fn test_drain_both_ends() {
+        let mut tree: TreeList<usize> = TreeList::new();
+        for i in 0..100 {
+            tree.push_back(i);
+        }
+
+        let mut drain = tree.drain(10..=20);
+        assert_eq!(drain.len(), 11);
+        assert_eq!(drain.next(), Some(10));
+        assert_eq!(drain.next_back(), Some(20));
+        assert_eq!(drain.next_back(), Some(19));
+        assert_eq!(drain.len(), 8);
+        assert_eq!(
+            drain.collect::<Vec<usize>>(),
+            (11..19).collect::<Vec<usize>>()
+        );
+
+        // Dropping an unfinished drain still removes the whole range.
+        tree.drain(..50);
+        check(&tree);
+        assert_eq!(tree.len(), 39);
+        assert_eq!(tree.get(0), Some(&61));
+
+        tree.drain(..);
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_drain_out_of_bounds() {
+        let mut tree: TreeList<usize> = TreeList::new();
+        tree.push_back(0);
+        tree.drain(0..2);
+    }
+
+    #[test]
+    fn test_iter_double_ended() {
+        let mut tree: TreeList<usize> = TreeList::new();
+        for i in 0..100 {
+            tree.push_back(i);
+        }
+
+        assert_eq!(
+            tree.iter().rev().copied().collect::<Vec<usize>>(),
+            (0..100).rev().collect::<Vec<usize>>()
+        );
+
+        let mut iter = tree.iter();
+        let mut expected = (0..100).collect::<Vec<usize>>().into_iter();
+        for i in 0..60 {
+            assert_eq!(iter.len(), expected.len());
+            if i % 3 == 0 {
+                assert_eq!(iter.next_back(), expected.next_back().as_ref());
+            } else {
+                assert_eq!(iter.next(), expected.next().as_ref());
+            }
+        }
+        assert_eq!(
+            iter.copied().collect::<Vec<usize>>(),
+            expected.collect::<Vec<usize>>()
+        );
+    }
+
+    #[test]
+    fn test_iter_skip() {
+        let mut tree: TreeList<usize, RedBlack> = TreeList::with_policy();
+        for i in 0..1000 {
+            tree.push_back(i);
+        }
+
+        assert_eq!(tree.iter().count(), 1000);
+        assert_eq!(tree.iter().last(), Some(&999));
+        assert_eq!(tree.iter().nth(1), Some(&1));
+        assert_eq!(tree.iter().nth(999), Some(&999));
+        assert_eq!(tree.iter().nth(1000), None);
+
+        let mut iter = tree.iter();
+        assert_eq!(iter.nth(10), Some(&10));
+        assert_eq!(iter.nth_back(10), Some(&989));
+        assert_eq!(iter.next(), Some(&11));
+        assert_eq!(iter.next_back(), Some(&988));
+        assert_eq!(iter.len(), 976);
+        assert_eq!(iter.nth(500), Some(&512));
+        assert_eq!(iter.nth_back(474), Some(&513));
+        assert_eq!(iter.len(), 0);
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+
+        let mut iter = tree.iter();
+        assert_eq!(iter.nth(2000), None);
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn test_cursor() {
+        let mut tree: TreeList<usize> = TreeList::new();
+        for i in 0..100 {
+            tree.push_back(i);
+        }
+
+        let mut cursor = tree.cursor_at(0);
+        for i in 0..100 {
+            assert_eq!(cursor.index(), Some(i));
+            assert_eq!(cursor.current(), Some(&i));
+            cursor.move_next();
+        }
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.current(), None);
+
+        // Moving past the ghost wraps around to the other end.
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&0));
+        cursor.move_prev();
+        assert_eq!(cursor.current(), None);
+        for i in (0..100).rev() {
+            cursor.move_prev();
+            assert_eq!(cursor.index(), Some(i));
+            assert_eq!(cursor.current(), Some(&i));
+        }
+
+        let mut cursor = tree.cursor_at(50);
+        cursor.move_prev();
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.current(), Some(&51));
+        assert_eq!(tree.cursor_at(100).index(), None);
+
+        let empty: TreeList<usize> = TreeList::new();
+        let mut cursor = empty.cursor_at(0);
+        cursor.move_next();
+        cursor.move_prev();
+        assert_eq!(cursor.current(), None);
+    }
+
+    #[test]
+    fn test_cursor_mut() {
+        let mut tree: TreeList<char> = TreeList::new();
+        tree.push_back('a');
+        tree.push_back('b');
+        tree.push_back('c');
+
+        let mut cursor = tree.cursor_at_mut(1);
+        cursor.insert_before('x');
+        cursor.insert_after('y');
+        assert_eq!(cursor.index(), Some(2));
+        assert_eq!(cursor.replace_current('B'), Some('b'));
+        assert_eq!(cursor.remove_current(), Some('B'));
+        assert_eq!(cursor.current(), Some(&mut 'y'));
+
+        cursor.move_next();
+        cursor.move_next();
+        assert_eq!(cursor.index(), None);
+        assert_eq!(cursor.remove_current(), None);
+        assert_eq!(cursor.replace_current('z'), None);
+        cursor.insert_before('d');
+        cursor.insert_after('0');
+        assert_eq!(cursor.index(), None);
+        cursor.move_prev();
+        assert_eq!(cursor.current(), Some(&mut 'd'));
+        drop(cursor);
+
+        check(&tree);
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<char>>(),
+            ['0', 'a', 'x', 'y', 'c', 'd'].to_vec()
+        );
+    }
+
+    fn cursor_mut_random<P: Validate>() {
+        let mut tree: TreeList<usize, P> = (0..1000).collect();
+        let mut vec: Vec<usize> = (0..1000).collect();
+        let mut index = 500;
+
+        for round in 0..5 {
+            let mut cursor = tree.cursor_at_mut(index);
+            for i in 0..3000 {
+                match (i * 7919 + round) % 7 {
+                    0..=2 => {
+                        cursor.move_next();
+                        index = if index == vec.len() { 0 } else { index + 1 };
+                    }
+                    3 | 4 => {
+                        cursor.move_prev();
+                        index = if index == 0 { vec.len() } else { index - 1 };
+                    }
+                    5 => {
+                        cursor.insert_before(i);
+                        vec.insert(index, i);
+                        index += 1;
+                    }
+                    _ if index < vec.len() => {
+                        assert_eq!(cursor.remove_current(), Some(vec.remove(index)));
+                    }
+                    _ => {
+                        cursor.insert_after(i);
+                        vec.insert(0, i);
+                        index += 1;
+                    }
+                }
+                let expected = if index < vec.len() { Some(index) } else { None };
+                assert_eq!(cursor.index(), expected);
+                assert_eq!(cursor.current().copied(), vec.get(index).copied());
+            }
+            drop(cursor);
+
+            check(&tree);
+            assert_eq!(tree.iter().copied().collect::<Vec<usize>>(), vec);
+        }
+    }
+
+    #[test]
+    fn test_cursor_mut_random_policies() {
+        cursor_mut_random::<Unbalanced>();
+        cursor_mut_random::<Avl>();
+        cursor_mut_random::<WeightBalanced>();
+        cursor_mut_random::<RedBlack>();
+    }
+
+    fn from_vec<P: Validate>(max_depth: fn(usize) -> usize) {
+        for len in (0..300).chain([1000, 4095, 4096, 10_000].iter().copied()) {
+            let tree: TreeList<usize, P> = TreeList::from_vec((0..len).collect());
+
+            check(&tree);
+            assert!(depth(&tree.root) <= max_depth(len));
+            assert_eq!(
+                tree.iter().copied().collect::<Vec<usize>>(),
+                (0..len).collect::<Vec<usize>>()
+            );
+        }
+    }
+
+    fn balanced_depth(len: usize) -> usize {
+        (usize::BITS - len.leading_zeros()) as usize
+    }
+
+    #[test]
+    fn test_from_vec_policies() {
+        from_vec::<Unbalanced>(balanced_depth);
+        from_vec::<Avl>(balanced_depth);
+        from_vec::<WeightBalanced>(balanced_depth);
+        from_vec::<RedBlack>(|len| 2 * balanced_depth(len));
+    }
+
+    #[test]
+    fn test_collect_then_edit() {
+        let mut tree: TreeList<usize, RedBlack> = (0..1000).filter(|i| i % 2 == 0).collect();
+        check(&tree);
+        assert_eq!(tree.len(), 500);
+
+        tree.extend(0..10);
+        tree.insert(250, 1);
+        assert_eq!(tree.remove(0), 0);
+        check(&tree);
+
+        let copy = tree.clone();
+        check(&copy);
+        assert_eq!(copy, tree);
+    }
+
+    #[test]
+    fn test_unbalanced() {
+        let mut tree: TreeList<usize, Unbalanced> = TreeList::with_policy();
+
+        for i in 0..100 {
+            tree.push_back(i);
+        }
+
+        check(&tree);
+        assert_eq!(depth(&tree.root), 100);
+    }
+
+    #[test]
+    fn test_iter_mut() {
+        let mut tree: TreeList<usize> = TreeList::new();
+        for i in 0..100 {
+            tree.push_back(i);
+        }
+
+        let iter = tree.iter_mut();
+        assert_eq!(iter.len(), 100);
+        for val in iter {
+            *val *= 2;
+        }
+
+        assert_eq!(
+            tree.iter().copied().collect::<Vec<usize>>(),
+            (0..100).map(|i| i * 2).collect::<Vec<usize>>()
+        );
+    }
+
+    #[test]
+    fn test_into_iter() {
+        let mut tree: TreeList<String> = TreeList::new();
+        for i in 0..100 {
+            tree.push_back(i.to_string());
+        }
+
+        let mut iter = tree.into_iter();
+        assert_eq!(iter.next(), Some("0".to_string()));
+        assert_eq!(iter.next_back(), Some("99".to_string()));
+        assert_eq!(iter.len(), 98);
+        assert_eq!(
+            iter.rev().take(3).collect::<Vec<String>>(),
+            ["98", "97", "96"]
+        );
+    }
+
+    #[test]
+    fn test_std_traits() {
+        let mut tree: TreeList<usize> = (0..10).collect();
+        tree.extend(10..15);
+        tree.extend(&[15, 16]);
+        tree[0] = 100;
+        assert_eq!(tree[0], 100);
+        assert_eq!(tree[16], 16);
+
+        for val in &mut tree {
+            *val += 1;
+        }
+        assert_eq!((&tree).into_iter().sum::<usize>(), 100 + 136 + 17);
+
+        let copy = tree.clone();
+        assert_eq!(copy, tree);
+        assert_eq!(format!("{:?}", TreeList::<usize>::from([1, 2])), "[1, 2]");
+        assert_eq!(
+            Vec::from(copy),
+            tree.iter().copied().collect::<Vec<usize>>()
+        );
+
+        // Equal sequences built in different orders give differently shaped trees.
+        let mut front: TreeList<usize> = TreeList::new();
+        for i in (0..100).rev() {
+            front.push_front(i);
+        }
+        let back: TreeList<usize> = TreeList::from((0..100).collect::<Vec<usize>>());
+        assert_eq!(front, back);
+
+        let mut hashes = std::collections::HashSet::new();
+        hashes.insert(front);
+        assert!(hashes.contains(&back));
+
+        let shorter: TreeList<usize> = TreeList::from([0, 1, 2]);
+        let larger: TreeList<usize> = TreeList::from([0, 2]);
+        assert!(shorter < back);
+        assert!(larger > back);
+        assert_eq!(shorter.cmp(&shorter.clone()), std::cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn test_get_out_of_bounds() {
+        let mut tree: TreeList<usize> = TreeList::new();
+        assert_eq!(tree.get(0), None);
+        assert_eq!(tree.get_mut(0), None);
+
+        tree.push_back(0);
+        tree.push_back(1);
+        assert_eq!(tree.get(2), None);
+        assert_eq!(tree.get_mut(2), None);
+        assert_eq!(tree.get(usize::MAX), None);
+    }
+
+    #[test]
+    fn test_try_methods() {
+        let mut tree: TreeList<usize> = (0..10).collect();
+
+        let err = tree.try_insert(11, 0).unwrap_err();
+        assert_eq!(err, TreeListError { index: 11, len: 10 });
+        assert_eq!(
+            err.to_string(),
+            "index 11 out of bounds for list of length 10"
+        );
+        assert_eq!(tree.try_insert(10, 10), Ok(()));
+
+        assert_eq!(
+            tree.try_remove(11),
+            Err(TreeListError { index: 11, len: 11 })
+        );
+        assert_eq!(tree.try_remove(10), Ok(10));
+
+        assert_eq!(
+            tree.try_swap(3, 10),
+            Err(TreeListError { index: 10, len: 10 })
+        );
+        assert_eq!(tree.try_swap(3, 3), Ok(()));
+        assert_eq!(tree.len(), 10);
+    }
+
+    #[test]
+    fn test_swap() {
+        let mut tree: TreeList<usize> = (0..100).collect();
+        let mut vec: Vec<usize> = (0..100).collect();
+
+        for i in 0..200 {
+            let (a, b) = ((i * 7919) % 100, (i * 104_729) % 100);
+            tree.swap(a, b);
+            vec.swap(a, b);
+        }
+
+        check(&tree);
+        assert_eq!(tree.iter().copied().collect::<Vec<usize>>(), vec);
+    }
+
+    #[test]
+    fn test_binary_search() {
+        let vec: Vec<usize> = (0..200).map(|i| i / 3 * 2).collect();
+        let tree: TreeList<usize> = vec.iter().copied().collect();
+
+        for x in 0..140 {
+            match (tree.binary_search(&x), vec.binary_search(&x)) {
+                (Ok(i), Ok(_)) => assert_eq!(tree[i], x),
+                (res, expected) => assert_eq!(res, expected),
+            }
+            assert_eq!(
+                tree.partition_point(|&val| val < x),
+                vec.partition_point(|&val| val < x)
+            );
+        }
+
+        let pairs: TreeList<(usize, char)> = TreeList::from([(1, 'a'), (3, 'b'), (5, 'c')]);
+        assert_eq!(pairs.binary_search_by_key(&3, |&(key, _)| key), Ok(1));
+        assert_eq!(pairs.binary_search_by_key(&4, |&(key, _)| key), Err(2));
+        assert_eq!(pairs.binary_search_by(|&(_, c)| c.cmp(&'z')), Err(3));
+        assert_eq!(TreeList::<usize>::new().binary_search(&0), Err(0));
+    }
 }
@@ -0,0 +1,413 @@
+use crate::balance::{Avl, BalancePolicy};
+use crate::tree_list::{Iter, TreeList};
+use std::ops::{Deref, DerefMut};
+
+// A single change, described by what it takes to revert it. Reverting one gives back the edit
+// that reverts the revert, so values move between the undo and redo stacks without cloning.
+#[derive(Debug)]
+enum Edit<T> {
+    Inserted(usize),
+    Removed(usize, T),
+    Replaced(usize, T),
+}
+
+impl<T> Edit<T> {
+    fn revert<P: BalancePolicy>(self, list: &mut TreeList<T, P>) -> Edit<T> {
+        match self {
+            Edit::Inserted(index) => Edit::Removed(index, list.remove(index)),
+            Edit::Removed(index, val) => {
+                list.insert(index, val);
+                Edit::Inserted(index)
+            }
+            Edit::Replaced(index, val) => {
+                let old = std::mem::replace(&mut list[index], val);
+                Edit::Replaced(index, old)
+            }
+        }
+    }
+}
+
+// Reverts a group of edits, last first, returning the edits that would revert them in turn.
+fn revert_all<T, P: BalancePolicy>(list: &mut TreeList<T, P>, edits: Vec<Edit<T>>) -> Vec<Edit<T>> {
+    edits
+        .into_iter()
+        .rev()
+        .map(|edit| edit.revert(list))
+        .collect()
+}
+
+// Every edit is logged as its inverse, at the index it happened at. Since edits are always
+// undone in reverse order, each one finds the list exactly as it left it, so the indices never
+// need adjusting. Edits made inside a transaction are undone and redone together.
+#[derive(Debug)]
+pub struct UndoTreeList<T, P: BalancePolicy = Avl> {
+    list: TreeList<T, P>,
+    undo: Vec<Vec<Edit<T>>>,
+    redo: Vec<Vec<Edit<T>>>,
+    transaction: Option<Vec<Edit<T>>>,
+}
+
+impl<T, P: BalancePolicy> Default for UndoTreeList<T, P> {
+    fn default() -> Self {
+        Self::with_policy()
+    }
+}
+
+impl<T> UndoTreeList<T> {
+    pub fn new() -> Self {
+        Self::with_policy()
+    }
+}
+
+impl<T, P: BalancePolicy> UndoTreeList<T, P> {
+    pub fn with_policy() -> Self {
+        UndoTreeList {
+            list: TreeList::with_policy(),
+            undo: Vec::new(),
+            redo: Vec::new(),
+            transaction: None,
+        }
+    }
+
+    // Any new edit outside of a transaction forks the history, so the redo stack is dropped.
+    fn record(&mut self, edit: Edit<T>) {
+        match &mut self.transaction {
+            Some(edits) => edits.push(edit),
+            None => {
+                self.undo.push(vec![edit]);
+                self.redo.clear();
+            }
+        }
+    }
+
+    pub fn get(&self, index: usize) -> Option<&T> {
+        self.list.get(index)
+    }
+
+    pub fn push_front(&mut self, val: T) {
+        self.insert(0, val);
+    }
+
+    pub fn push_back(&mut self, val: T) {
+        self.insert(self.len(), val);
+    }
+
+    pub fn insert(&mut self, index: usize, val: T) {
+        self.list.insert(index, val);
+        self.record(Edit::Inserted(index));
+    }
+
+    /// Starts grouping edits, so that they are undone as one. Panics if a transaction is
+    /// already open.
+    pub fn begin_transaction(&mut self) {
+        if self.transaction.is_some() {
+            panic!("Transaction already open!");
+        } else {
+            self.transaction = Some(Vec::new());
+        }
+    }
+
+    /// Closes the open transaction, adding its edits to the history as a single step. Panics if
+    /// no transaction is open.
+    pub fn commit(&mut self) {
+        let edits = self.transaction.take().expect("No transaction open!");
+        if !edits.is_empty() {
+            self.undo.push(edits);
+            self.redo.clear();
+        }
+    }
+
+    /// Reverts every edit made since the open transaction began and closes it, leaving no
+    /// trace in the history. Panics if no transaction is open.
+    pub fn rollback(&mut self) {
+        let edits = self.transaction.take().expect("No transaction open!");
+        revert_all(&mut self.list, edits);
+    }
+
+    /// Reverts the latest step in the history, returning whether there was one. Panics if a
+    /// transaction is open.
+    pub fn undo(&mut self) -> bool {
+        if self.transaction.is_some() {
+            panic!("Cannot undo during a transaction!");
+        }
+
+        match self.undo.pop() {
+            None => false,
+            Some(edits) => {
+                let edits = revert_all(&mut self.list, edits);
+                self.redo.push(edits);
+                true
+            }
+        }
+    }
+
+    /// Reapplies the latest undone step, returning whether there was one. Panics if a
+    /// transaction is open.
+    pub fn redo(&mut self) -> bool {
+        if self.transaction.is_some() {
+            panic!("Cannot redo during a transaction!");
+        }
+
+        match self.redo.pop() {
+            None => false,
+            Some(edits) => {
+                let edits = revert_all(&mut self.list, edits);
+                self.undo.push(edits);
+                true
+            }
+        }
+    }
+
+    pub fn can_undo(&self) -> bool {
+        !self.undo.is_empty()
+    }
+
+    pub fn can_redo(&self) -> bool {
+        !self.redo.is_empty()
+    }
+
+    /// Forgets every step that could be undone or redone, keeping the current contents.
+    pub fn clear_history(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+
+    pub fn len(&self) -> usize {
+        self.list.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.list.is_empty()
+    }
+
+    pub fn iter(&self) -> Iter<'_, T, P> {
+        self.list.iter()
+    }
+}
+
+// The log keeps its own copy of anything taken out of the list, hence the bound.
+impl<T: Clone, P: BalancePolicy> UndoTreeList<T, P> {
+    pub fn pop_front(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(0))
+        }
+    }
+
+    pub fn pop_back(&mut self) -> Option<T> {
+        if self.is_empty() {
+            None
+        } else {
+            Some(self.remove(self.len() - 1))
+        }
+    }
+
+    pub fn remove(&mut self, index: usize) -> T {
+        let val = self.list.remove(index);
+        self.record(Edit::Removed(index, val.clone()));
+        val
+    }
+
+    // Writes through the returned guard are logged when it is dropped. The old value is cloned
+    // on the first write, so reading through the guard costs nothing extra.
+    pub fn get_mut(&mut self, index: usize) -> Option<ValueMut<'_, T, P>> {
+        if index >= self.len() {
+            None
+        } else {
+            Some(ValueMut {
+                list: self,
+                index,
+                old: None,
+            })
+        }
+    }
+}
+
+// Every access goes back through the list, so each dereference costs `O(log n)`.
+pub struct ValueMut<'a, T: Clone, P: BalancePolicy> {
+    list: &'a mut UndoTreeList<T, P>,
+    index: usize,
+    old: Option<T>,
+}
+
+impl<'a, T: Clone, P: BalancePolicy> Deref for ValueMut<'a, T, P> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.list.list[self.index]
+    }
+}
+
+impl<'a, T: Clone, P: BalancePolicy> DerefMut for ValueMut<'a, T, P> {
+    fn deref_mut(&mut self) -> &mut T {
+        let val = &mut self.list.list[self.index];
+        if self.old.is_none() {
+            self.old = Some(val.clone());
+        }
+        val
+    }
+}
+
+impl<'a, T: Clone, P: BalancePolicy> Drop for ValueMut<'a, T, P> {
+    fn drop(&mut self) {
+        if let Some(old) = self.old.take() {
+            self.list.record(Edit::Replaced(self.index, old));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UndoTreeList;
+    use crate::balance::RedBlack;
+
+    fn contents(tree: &UndoTreeList<char>) -> String {
+        tree.iter().collect()
+    }
+
+    #[test]
+    fn test_empty_tree() {
+        let mut tree: UndoTreeList<char> = UndoTreeList::new();
+
+        assert_eq!(tree.len(), 0);
+        assert!(!tree.can_undo());
+        assert!(!tree.undo());
+        assert!(!tree.redo());
+        assert!(tree.get_mut(0).is_none());
+        assert_eq!(tree.pop_back(), None);
+    }
+
+    #[test]
+    fn test_undo_redo() {
+        let mut tree: UndoTreeList<char> = UndoTreeList::new();
+        tree.push_back('a');
+        tree.push_back('b');
+        tree.push_front('c');
+        assert_eq!(tree.remove(1), 'a');
+        *tree.get_mut(0).unwrap() = 'x';
+        assert_eq!(contents(&tree), "xb");
+
+        assert!(tree.undo());
+        assert_eq!(contents(&tree), "cb");
+        assert!(tree.undo());
+        assert_eq!(contents(&tree), "cab");
+        assert!(tree.undo());
+        assert!(tree.undo());
+        assert_eq!(contents(&tree), "a");
+
+        assert!(tree.redo());
+        assert!(tree.redo());
+        assert!(tree.redo());
+        assert_eq!(contents(&tree), "cb");
+        assert!(tree.can_redo());
+
+        tree.push_back('d');
+        assert!(!tree.can_redo());
+        assert!(!tree.redo());
+        assert_eq!(contents(&tree), "cbd");
+    }
+
+    #[test]
+    fn test_get_mut_reads_are_not_logged() {
+        let mut tree: UndoTreeList<char> = UndoTreeList::new();
+        tree.push_back('a');
+        tree.clear_history();
+
+        assert_eq!(*tree.get_mut(0).unwrap(), 'a');
+        assert!(!tree.can_undo());
+
+        {
+            let mut val = tree.get_mut(0).unwrap();
+            *val = 'b';
+            *val = 'c';
+        }
+        assert!(tree.undo());
+        assert_eq!(contents(&tree), "a");
+        assert!(!tree.can_undo());
+    }
+
+    #[test]
+    fn test_transactions() {
+        let mut tree: UndoTreeList<char> = UndoTreeList::new();
+        tree.push_back('a');
+
+        tree.begin_transaction();
+        tree.push_back('b');
+        tree.push_front('c');
+        *tree.get_mut(1).unwrap() = 'x';
+        tree.commit();
+        assert_eq!(contents(&tree), "cxb");
+
+        tree.begin_transaction();
+        tree.pop_front();
+        tree.push_back('d');
+        assert_eq!(contents(&tree), "xbd");
+        tree.rollback();
+        assert_eq!(contents(&tree), "cxb");
+
+        tree.begin_transaction();
+        tree.commit();
+
+        assert!(tree.undo());
+        assert_eq!(contents(&tree), "a");
+        assert!(tree.redo());
+        assert_eq!(contents(&tree), "cxb");
+        assert!(tree.undo());
+        assert!(tree.undo());
+        assert!(!tree.undo());
+        assert!(tree.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "Cannot undo during a transaction!")]
+    fn test_undo_during_transaction() {
+        let mut tree: UndoTreeList<char> = UndoTreeList::new();
+        tree.begin_transaction();
+        tree.undo();
+    }
+
+    #[test]
+    fn test_random_history() {
+        let mut tree: UndoTreeList<usize, RedBlack> = UndoTreeList::with_policy();
+        let mut vec: Vec<usize> = Vec::new();
+        let mut snapshots: Vec<Vec<usize>> = vec![Vec::new()];
+
+        for i in 0..300 {
+            let in_transaction = i % 7 == 0;
+            if in_transaction {
+                tree.begin_transaction();
+            }
+            for j in 0..if in_transaction { 5 } else { 1 } {
+                let step = i * 5 + j;
+                let index = (step * 7919) % (vec.len() + 1);
+                if step % 3 == 2 && !vec.is_empty() {
+                    let index = index % vec.len();
+                    assert_eq!(tree.remove(index), vec.remove(index));
+                } else if step % 3 == 1 && !vec.is_empty() {
+                    let index = index % vec.len();
+                    *tree.get_mut(index).unwrap() += 1000;
+                    vec[index] += 1000;
+                } else {
+                    tree.insert(index, step);
+                    vec.insert(index, step);
+                }
+            }
+            if in_transaction {
+                tree.commit();
+            }
+            snapshots.push(vec.clone());
+        }
+
+        for expected in snapshots.iter().rev().skip(1) {
+            assert!(tree.undo());
+            assert_eq!(&tree.iter().copied().collect::<Vec<usize>>(), expected);
+        }
+        assert!(!tree.undo());
+        for expected in snapshots.iter().skip(1) {
+            assert!(tree.redo());
+            assert_eq!(&tree.iter().copied().collect::<Vec<usize>>(), expected);
+        }
+        assert!(!tree.redo());
+    }
+}